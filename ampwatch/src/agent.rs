@@ -1,13 +1,131 @@
-use std::fs;
-use std::process::Command;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::mem;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
 
-#[derive(Clone)]
+use chrono::NaiveDateTime;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{Config, DEFAULT_MODEL_PATTERN};
+use crate::instance::kill_session;
+use crate::runner::CommandRunner;
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum AgentType {
     Reviewer,
     Implementer,
 }
 
+/// Agent lifecycle derived from its tmux session and log markers
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AgentStatus {
+    Running,
+    /// Stopped after its last iteration exited cleanly
+    Completed,
+    /// Stopped mid-iteration or after a non-zero exit
+    Crashed,
+    /// No session and no log activity
+    Idle,
+}
+
+/// Where an agent's iteration count and activity were read from
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusSource {
+    /// `{logs_dir}/{name}.status.json`, preferred when present
+    StatusFile,
+    /// Scraped from `{logs_dir}/{name}.log`
+    Log,
+}
+
+/// Schema of the optional per-agent status file. Every field is optional so
+/// partial writers are tolerated:
+///
+/// ```json
+/// {"state": "running", "iteration": 3, "task": "Reviewing PR #12", "exit_code": null}
+/// ```
+///
+/// `state` is one of `running`, `completed`, `crashed`, or `idle`.
+#[derive(Deserialize)]
+struct StatusFile {
+    state: Option<String>,
+    iteration: Option<u32>,
+    task: Option<String>,
+    exit_code: Option<i32>,
+}
+
+/// Whole words that mark a log line as an error, so `error_handling.rs`
+/// doesn't count
+const ERROR_WORDS: &str = r"(?i)\b(errors?|panic|panicked|failed|failures?)\b";
+
+/// Counts of nothing, like "0 errors", "no errors found" or "failed: 0", cut
+/// out of a line before looking for `ERROR_WORDS`
+const ZERO_COUNTS: &str = r"(?i)\b(0|no|zero|without)\s+(errors?|panics?|failed|failures?)\b|\b(errors?|panics?|failed|failures?)\s*[:=]\s*0\b";
+
+/// Lines an error stays "recent" for; older ones are assumed resolved
+const ERROR_TAIL_LINES: u32 = 200;
+
+/// How much of a log's end the first read scans, so a long-running agent's
+/// log isn't read from the top
+const LOG_TAIL_BYTES: u64 = 1024 * 1024;
+
+/// What to look for in agent logs besides the launcher's markers
 #[derive(Clone)]
+pub struct LogScan {
+    error_words: Regex,
+    zero_counts: Regex,
+    /// Also marks a line as an error, from `error_pattern`
+    error_pattern: Option<Regex>,
+    model_pattern: Option<Regex>,
+}
+
+impl Default for LogScan {
+    fn default() -> Self {
+        Self {
+            error_words: Regex::new(ERROR_WORDS).unwrap(),
+            zero_counts: Regex::new(ZERO_COUNTS).unwrap(),
+            error_pattern: None,
+            model_pattern: Regex::new(DEFAULT_MODEL_PATTERN).ok(),
+        }
+    }
+}
+
+impl LogScan {
+    /// Patterns are validated when the config loads, so a bad one is absent
+    pub fn new(config: &Config) -> Self {
+        Self {
+            error_pattern: config
+                .error_pattern
+                .as_deref()
+                .and_then(|p| Regex::new(p).ok()),
+            model_pattern: Regex::new(&config.model_pattern).ok(),
+            ..Self::default()
+        }
+    }
+
+    /// The model a log line records: `model_pattern`'s first capture group,
+    /// or its whole match if it has none
+    fn model(&self, line: &str) -> Option<String> {
+        let captures = self.model_pattern.as_ref()?.captures(line)?;
+        let model = captures.get(1).or_else(|| captures.get(0))?;
+        Some(model.as_str().to_string())
+    }
+
+    fn is_error(&self, line: &str) -> bool {
+        self.error_words
+            .is_match(&self.zero_counts.replace_all(line, ""))
+            || self
+                .error_pattern
+                .as_ref()
+                .is_some_and(|re| re.is_match(line))
+    }
+}
+
+#[derive(Clone, Serialize)]
 pub struct Agent {
     pub name: String,
     pub agent_type: AgentType,
@@ -15,6 +133,41 @@ pub struct Agent {
     pub is_running: bool,
     pub iterations: u32,
     pub last_activity: String,
+    /// Exit code of the last finished iteration, `None` if one is in progress
+    pub last_exit_code: Option<i32>,
+    /// State reported by the status file, used when the session isn't running
+    pub reported_status: Option<AgentStatus>,
+    pub status_source: Option<StatusSource>,
+    /// Other agents whose iteration markers appear in this agent's log
+    pub foreign_log_agents: Vec<String>,
+    /// Shell command amptown runs in the agent's tmux session, rebuilt from
+    /// `{logs_dir}/{name}-launcher.sh` when it exists
+    pub launch_cmd: Option<String>,
+    /// Model from the first log line matching `model_pattern`
+    pub model: Option<String>,
+    /// Latest error in the log's recent tail; cleared when a new iteration
+    /// starts or `ERROR_TAIL_LINES` lines pass without another
+    pub last_error_line: Option<String>,
+    #[serde(skip)]
+    lines_since_error: u32,
+    /// When `{name}.log` was last written, used to spot hung agents
+    #[serde(skip)]
+    pub last_log_mtime: Option<SystemTime>,
+    /// Times of the first and latest iteration markers whose timestamps
+    /// parsed, and how many markers that is
+    #[serde(skip)]
+    marker_span: Option<(NaiveDateTime, NaiveDateTime, u32)>,
+    /// Bytes of `{name}.log` already scanned
+    #[serde(skip)]
+    log_offset: u64,
+    /// Inode and mtime of `{name}.log` when it was last read, to notice it
+    /// being replaced by a file at least as long
+    #[serde(skip)]
+    log_stamp: Option<(u64, SystemTime)>,
+    /// The scan started partway into the log, so the first marker's number
+    /// stands in for the iterations before it
+    #[serde(skip)]
+    log_tail_start: bool,
 }
 
 impl Agent {
@@ -26,6 +179,19 @@ impl Agent {
             is_running: false,
             iterations: 0,
             last_activity: String::new(),
+            last_exit_code: None,
+            reported_status: None,
+            status_source: None,
+            foreign_log_agents: Vec::new(),
+            launch_cmd: None,
+            model: None,
+            last_error_line: None,
+            lines_since_error: 0,
+            last_log_mtime: None,
+            marker_span: None,
+            log_offset: 0,
+            log_stamp: None,
+            log_tail_start: false,
         }
     }
 
@@ -33,38 +199,560 @@ impl Agent {
         format!("amptown-{}-{}", self.instance_id, self.name)
     }
 
-    pub fn refresh(&mut self, logs_dir: &Option<String>) {
-        self.check_running();
+    pub fn log_path(&self, logs_dir: &str) -> String {
+        format!("{}/{}.log", logs_dir, self.name)
+    }
+
+    /// How long a running agent's log has gone without a write, if that
+    /// exceeds `threshold`
+    pub fn stuck_for(&self, threshold: Duration) -> Option<Duration> {
+        if !self.is_running {
+            return None;
+        }
+        let quiet = self.last_log_mtime?.elapsed().ok()?;
+        (quiet > threshold).then_some(quiet)
+    }
+
+    /// Iterations started per hour, from the timestamps on the log's
+    /// iteration markers. `None` until two markers with parseable
+    /// timestamps are seen.
+    pub fn iterations_per_hour(&self) -> Option<f64> {
+        let (first, last, count) = self.marker_span?;
+        let secs = (last - first).num_seconds();
+        (count > 1 && secs > 0).then(|| f64::from(count - 1) * 3600.0 / secs as f64)
+    }
+
+    /// `model`, or `unknown` if the log never named one
+    pub fn model_label(&self) -> &str {
+        self.model.as_deref().unwrap_or("unknown")
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.last_error_line.is_some()
+    }
+
+    pub fn status(&self) -> AgentStatus {
+        if self.is_running {
+            AgentStatus::Running
+        } else if let Some(status) = self.reported_status {
+            // A file still claiming "running" without a session means the agent died
+            if status == AgentStatus::Running {
+                AgentStatus::Crashed
+            } else {
+                status
+            }
+        } else if self.iterations == 0 {
+            AgentStatus::Idle
+        } else if self.last_exit_code == Some(0) {
+            AgentStatus::Completed
+        } else {
+            AgentStatus::Crashed
+        }
+    }
+
+    pub fn refresh(
+        &mut self,
+        logs_dir: &Option<String>,
+        runner: &dyn CommandRunner,
+        log_scan: &LogScan,
+    ) {
+        self.check_running(runner);
         if let Some(dir) = logs_dir {
-            self.read_log(dir);
+            self.find_launch_cmd(dir);
+            // Read even when a status file is present; the log is what
+            // keeps growing while amp works
+            self.last_log_mtime = fs::metadata(self.log_path(dir))
+                .and_then(|m| m.modified())
+                .ok();
+            self.status_source = if self.read_status_file(dir) {
+                Some(StatusSource::StatusFile)
+            } else if self.read_log(dir, log_scan) {
+                Some(StatusSource::Log)
+            } else {
+                None
+            };
+        }
+    }
+
+    /// Mirror the command amptown passes to `tmux new-session`
+    fn find_launch_cmd(&mut self, logs_dir: &str) {
+        let launcher = format!("{}/{}-launcher.sh", logs_dir, self.name);
+        self.launch_cmd = Path::new(&launcher).is_file().then(|| {
+            format!(
+                "'{}' '{}/{}-prompt.txt' '{}' '{}'",
+                launcher,
+                logs_dir,
+                self.name,
+                self.log_path(logs_dir),
+                self.name
+            )
+        });
+    }
+
+    /// Kill the agent's tmux session, if any, and start it again in `repo_path`
+    pub fn restart(&self, runner: &dyn CommandRunner, repo_path: &str) -> Result<(), String> {
+        let Some(cmd) = &self.launch_cmd else {
+            return Err("launch command unknown".to_string());
+        };
+        let session = self.session_name();
+        kill_session(runner, &session);
+
+        let output = runner
+            .run(
+                "tmux",
+                &["new-session", "-d", "-s", &session, "-c", repo_path, cmd],
+                None,
+            )
+            .map_err(|e| format!("failed to run tmux: {}", e))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+        }
+    }
+
+    /// Type `text` into the agent's tmux pane and press Enter
+    pub fn send_keys(&self, runner: &dyn CommandRunner, text: &str) -> Result<(), String> {
+        let session = self.session_name();
+        // -l sends the text literally, so words like "Enter" aren't key names
+        for args in [
+            &["send-keys", "-t", &session, "-l", text][..],
+            &["send-keys", "-t", &session, "Enter"][..],
+        ] {
+            let output = runner
+                .run("tmux", args, None)
+                .map_err(|e| format!("failed to run tmux: {}", e))?;
+            if !output.status.success() {
+                return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Last `lines` lines of the agent's live tmux pane, ignoring the blank
+    /// rows below the cursor
+    pub fn capture_pane(
+        &self,
+        runner: &dyn CommandRunner,
+        lines: usize,
+    ) -> Result<Vec<String>, String> {
+        let session = self.session_name();
+        let output = runner
+            .run("tmux", &["capture-pane", "-t", &session, "-p"], None)
+            .map_err(|e| format!("failed to run tmux: {}", e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            return Err(if stderr.is_empty() {
+                format!("no pane for {}", session)
+            } else {
+                stderr
+            });
         }
+
+        let text = String::from_utf8_lossy(&output.stdout);
+        let mut captured: Vec<String> = text.lines().map(|l| l.trim_end().to_string()).collect();
+        while captured.last().is_some_and(|l| l.is_empty()) {
+            captured.pop();
+        }
+        let skip = captured.len().saturating_sub(lines);
+        captured.drain(..skip);
+        Ok(captured)
+    }
+
+    /// Read `{name}.status.json`, returning false if absent or unparseable
+    fn read_status_file(&mut self, logs_dir: &str) -> bool {
+        let path = format!("{}/{}.status.json", logs_dir, self.name);
+        let Some(status) = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<StatusFile>(&content).ok())
+        else {
+            return false;
+        };
+
+        self.iterations = status.iteration.unwrap_or(0);
+        self.last_exit_code = status.exit_code;
+        self.reported_status = match status.state.as_deref() {
+            Some("running") => Some(AgentStatus::Running),
+            Some("completed") => Some(AgentStatus::Completed),
+            Some("crashed") => Some(AgentStatus::Crashed),
+            Some("idle") => Some(AgentStatus::Idle),
+            _ => None,
+        };
+        if let Some(task) = status.task {
+            self.last_activity = task.chars().take(80).collect();
+        }
+        true
     }
 
-    fn check_running(&mut self) {
+    fn check_running(&mut self, runner: &dyn CommandRunner) {
         let session_name = self.session_name();
 
-        let output = Command::new("tmux")
-            .args(["has-session", "-t", &session_name])
-            .output();
+        let output = runner.run("tmux", &["has-session", "-t", &session_name], None);
 
         self.is_running = output.map(|o| o.status.success()).unwrap_or(false);
     }
 
-    fn read_log(&mut self, logs_dir: &str) {
-        let log_path = format!("{}/{}.log", logs_dir, self.name);
+    /// Scrape progress from `{name}.log`, returning false if it can't be
+    /// read. The first read starts `LOG_TAIL_BYTES` from the end; after that
+    /// only bytes appended since the last refresh are scanned. A log that
+    /// shrank, or was replaced (new inode, older mtime), is rescanned.
+    fn read_log(&mut self, logs_dir: &str, log_scan: &LogScan) -> bool {
+        let log_path = self.log_path(logs_dir);
+        self.reported_status = None;
 
-        if let Ok(content) = fs::read_to_string(&log_path) {
-            // Count iterations
-            self.iterations = content.matches("Starting").count() as u32;
+        let Ok(file) = File::open(&log_path) else {
+            return false;
+        };
+        let meta = file.metadata().ok();
+        let len = meta.as_ref().map_or(0, |m| m.len());
+        let stamp = meta.and_then(|m| Some((file_id(&m), m.modified().ok()?)));
+        let replaced = match (self.log_stamp, stamp) {
+            (Some((old_id, old_mtime)), Some((id, mtime))) => id != old_id || mtime < old_mtime,
+            _ => false,
+        };
+        self.log_stamp = stamp;
+        if len < self.log_offset || replaced {
+            self.log_offset = 0;
+        }
+        if self.log_offset == 0 {
+            self.iterations = 0;
+            self.last_exit_code = None;
+            self.last_activity.clear();
+            self.foreign_log_agents.clear();
+            self.marker_span = None;
+            self.last_error_line = None;
+            self.model = None;
+            self.log_tail_start = false;
+        }
 
-            // Get last meaningful line
-            let lines: Vec<&str> = content.lines().collect();
-            for line in lines.iter().rev() {
-                if !line.starts_with('[') && !line.trim().is_empty() {
-                    self.last_activity = line.chars().take(80).collect();
-                    break;
+        let mut reader = BufReader::new(file);
+        let mut buf = Vec::new();
+        if self.log_offset == 0 && len > LOG_TAIL_BYTES {
+            // Skip to the first whole line of the tail
+            self.log_offset = len - LOG_TAIL_BYTES;
+            self.log_tail_start = true;
+            if reader.seek(SeekFrom::Start(self.log_offset)).is_err() {
+                return true;
+            }
+            if let Ok(n) = reader.read_until(b'\n', &mut buf) {
+                if buf.ends_with(b"\n") {
+                    self.log_offset += n as u64;
+                }
+            }
+        } else if len == self.log_offset || reader.seek(SeekFrom::Start(self.log_offset)).is_err() {
+            return true;
+        }
+        loop {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf) {
+                // Leave a partial last line for the next refresh
+                Ok(n) if n > 0 && buf.ends_with(b"\n") => {
+                    self.log_offset += n as u64;
+                    self.scan_log_line(String::from_utf8_lossy(&buf).trim_end(), log_scan);
+                }
+                _ => break,
+            }
+        }
+        true
+    }
+
+    /// Update progress from one complete log line
+    fn scan_log_line(&mut self, line: &str, log_scan: &LogScan) {
+        if let Some((name, iteration)) = iteration_start(line) {
+            if name == self.name {
+                // The launcher brackets each iteration with timestamped marker lines
+                self.iterations = if mem::take(&mut self.log_tail_start) {
+                    iteration
+                } else {
+                    self.iterations + 1
+                };
+                self.last_exit_code = None;
+                self.last_error_line = None;
+                if let Some(time) = marker_time(line) {
+                    self.marker_span = Some(match self.marker_span {
+                        Some((first, _, count)) => (first, time, count + 1),
+                        None => (time, time, 1),
+                    });
                 }
+            } else if !self.foreign_log_agents.iter().any(|n| n == name) {
+                // Markers naming another agent mean two agents share this file
+                self.foreign_log_agents.push(name.to_string());
+            }
+            return;
+        }
+
+        if self.model.is_none() {
+            self.model = log_scan.model(line);
+        }
+        if log_scan.is_error(line) {
+            self.last_error_line = Some(line.trim().chars().take(200).collect());
+            self.lines_since_error = 0;
+        } else if self.last_error_line.is_some() {
+            self.lines_since_error += 1;
+            if self.lines_since_error > ERROR_TAIL_LINES {
+                self.last_error_line = None;
+            }
+        }
+        if line.starts_with('[') {
+            if let Some((_, rest)) = line.split_once("ended (exit code: ") {
+                self.last_exit_code = rest.trim_end_matches(')').parse().ok();
             }
+        } else if !line.trim().is_empty() {
+            self.last_activity = line.chars().take(80).collect();
+        }
+    }
+
+    /// Keep the log scan position and what was scraped before it, so the
+    /// next `read_log` only reads new bytes
+    pub fn carry_over(&mut self, prev: &Agent) {
+        if prev.status_source != Some(StatusSource::Log) {
+            return;
+        }
+        self.log_offset = prev.log_offset;
+        self.log_stamp = prev.log_stamp;
+        self.log_tail_start = prev.log_tail_start;
+        self.iterations = prev.iterations;
+        self.last_exit_code = prev.last_exit_code;
+        self.last_activity = prev.last_activity.clone();
+        self.foreign_log_agents = prev.foreign_log_agents.clone();
+        self.marker_span = prev.marker_span;
+        self.model = prev.model.clone();
+        self.last_error_line = prev.last_error_line.clone();
+        self.lines_since_error = prev.lines_since_error;
+    }
+}
+
+/// Inode of an open log, which changes when the file is rotated
+#[cfg(unix)]
+fn file_id(meta: &fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    meta.ino()
+}
+
+#[cfg(not(unix))]
+fn file_id(_meta: &fs::Metadata) -> u64 {
+    0
+}
+
+/// Parse the launcher's iteration marker, `[<date>] Starting <name> iteration <n>`,
+/// returning the agent name and iteration number. Anything else, including
+/// amp output that merely mentions "Starting", is not a marker.
+fn iteration_start(line: &str) -> Option<(&str, u32)> {
+    let (_, rest) = line.strip_prefix('[')?.split_once("] ")?;
+    let mut words = rest.strip_prefix("Starting ")?.split(' ');
+    let name = words.next()?;
+    if words.next()? != "iteration" {
+        return None;
+    }
+    let iteration = words.next()?.trim_end().parse().ok()?;
+    words.next().is_none().then_some((name, iteration))
+}
+
+/// Timestamp of a launcher marker line, written by `date` as
+/// `[Mon May  6 10:00:00 UTC 2024]`. The zone name is skipped; only the
+/// differences between markers are used.
+fn marker_time(line: &str) -> Option<NaiveDateTime> {
+    let (stamp, _) = line.strip_prefix('[')?.split_once("] ")?;
+    NaiveDateTime::parse_from_str(stamp, "%a %b %e %H:%M:%S %Z %Y").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_LOG: &str = "\
+[Mon May  6 10:00:00 UTC 2024] Starting impl-alpha iteration 1
+Starting to write tests for the parser
+Restarting build after dependency change
+[build] Starting compilation
+[Mon May  6 10:20:00 UTC 2024] impl-alpha iteration 1 ended (exit code: 1)
+[Mon May  6 10:20:05 UTC 2024] Starting impl-alpha iteration 2
+Starting iteration over the remaining files
+";
+
+    fn scan(name: &str, log: &str) -> Agent {
+        let mut agent = Agent::new(name, AgentType::Implementer, "abc123".to_string());
+        for line in log.lines() {
+            agent.scan_log_line(line, &LogScan::default());
+        }
+        agent
+    }
+
+    #[test]
+    fn counts_only_launcher_markers() {
+        let agent = scan("impl-alpha", SAMPLE_LOG);
+        assert_eq!(agent.iterations, 2);
+        assert_eq!(agent.last_exit_code, None);
+        assert_eq!(
+            agent.last_activity,
+            "Starting iteration over the remaining files"
+        );
+    }
+
+    #[test]
+    fn ignores_other_agents_markers() {
+        let log = format!(
+            "{}[Mon May  6 10:30:00 UTC 2024] Starting impl-beta iteration 1\n",
+            SAMPLE_LOG
+        );
+        assert_eq!(scan("impl-alpha", &log).iterations, 2);
+        assert_eq!(scan("impl-beta", &log).iterations, 1);
+        assert_eq!(scan("impl-alpha", &log).foreign_log_agents, ["impl-beta"]);
+    }
+
+    #[test]
+    fn iteration_rate_from_marker_timestamps() {
+        // Two markers 20m05s apart
+        let rate = scan("impl-alpha", SAMPLE_LOG)
+            .iterations_per_hour()
+            .unwrap();
+        assert!((rate - 2.99).abs() < 0.01, "rate was {}", rate);
+
+        let one = "[Mon May  6 10:00:00 UTC 2024] Starting impl-alpha iteration 1\n";
+        assert_eq!(scan("impl-alpha", one).iterations_per_hour(), None);
+        let unparseable = "[yesterday] Starting impl-alpha iteration 1\n\
+                           [today] Starting impl-alpha iteration 2\n";
+        assert_eq!(scan("impl-alpha", unparseable).iterations, 2);
+        assert_eq!(scan("impl-alpha", unparseable).iterations_per_hour(), None);
+    }
+
+    #[test]
+    fn keeps_only_recent_errors() {
+        let log = "[Mon May  6 10:00:00 UTC 2024] Starting impl-alpha iteration 1\n\
+                   error[E0308]: mismatched types\n\
+                   Compiling parser\n";
+        let agent = scan("impl-alpha", log);
+        assert_eq!(
+            agent.last_error_line.as_deref(),
+            Some("error[E0308]: mismatched types")
+        );
+
+        // A new iteration starts clean
+        let next = format!(
+            "{}[Mon May  6 10:20:05 UTC 2024] Starting impl-alpha iteration 2\n",
+            log
+        );
+        assert!(!scan("impl-alpha", &next).has_errors());
+
+        let scrolled = format!(
+            "{}{}",
+            log,
+            "still working\n".repeat(ERROR_TAIL_LINES as usize + 1)
+        );
+        assert!(!scan("impl-alpha", &scrolled).has_errors());
+
+        let mut agent = Agent::new("impl-alpha", AgentType::Implementer, "abc123".to_string());
+        let scan = LogScan {
+            error_pattern: Regex::new("^FAIL ").ok(),
+            ..LogScan::default()
+        };
+        agent.scan_log_line("FAIL tests/parser.rs", &scan);
+        assert!(agent.has_errors());
+    }
+
+    #[test]
+    fn error_words_need_a_nonzero_count() {
+        let scan = LogScan::default();
+        for line in [
+            "thread 'main' panicked at src/lib.rs:10:5",
+            "Build FAILED",
+            "test result: FAILED. 3 passed; 2 failed",
+            "0 failed; 1 error",
+        ] {
+            assert!(scan.is_error(line), "{line}");
         }
+        for line in [
+            "Compiled with 0 errors",
+            "no errors found",
+            "test result: ok. 12 passed; 0 failed",
+            "failed: 0",
+            "Editing src/error_handling.rs",
+            "Renamed errorCode to status",
+        ] {
+            assert!(!scan.is_error(line), "{line}");
+        }
+    }
+
+    #[test]
+    fn records_first_model_line() {
+        let log = "Starting amp with model=claude-sonnet temperature=0\n\
+                   switching model: gpt-5\n";
+        assert_eq!(scan("impl-alpha", log).model_label(), "claude-sonnet");
+        assert_eq!(scan("impl-alpha", SAMPLE_LOG).model_label(), "unknown");
+    }
+
+    #[test]
+    fn parses_marker_name_and_iteration() {
+        assert_eq!(
+            iteration_start("[Mon May  6 10:20:05 UTC 2024] Starting impl-alpha iteration 12"),
+            Some(("impl-alpha", 12))
+        );
+        assert_eq!(iteration_start("[build] Starting compilation"), None);
+        assert_eq!(iteration_start("Starting impl-alpha iteration 1"), None);
+    }
+
+    #[test]
+    fn reads_appended_lines_and_rescans_truncated_log() {
+        let dir = std::env::temp_dir().join(format!("ampwatch-log-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let logs_dir = dir.to_string_lossy().to_string();
+        let mut agent = Agent::new("impl-alpha", AgentType::Implementer, "abc123".to_string());
+        let path = agent.log_path(&logs_dir);
+
+        fs::write(&path, SAMPLE_LOG).unwrap();
+        assert!(agent.read_log(&logs_dir, &LogScan::default()));
+        assert_eq!(agent.iterations, 2);
+
+        let mut appended = SAMPLE_LOG.to_string();
+        appended.push_str(
+            "[Mon May  6 11:00:00 UTC 2024] impl-alpha iteration 2 ended (exit code: 0)\n",
+        );
+        fs::write(&path, &appended).unwrap();
+        assert!(agent.read_log(&logs_dir, &LogScan::default()));
+        assert_eq!(agent.iterations, 2);
+        assert_eq!(agent.last_exit_code, Some(0));
+
+        fs::write(
+            &path,
+            "[Mon May  6 12:00:00 UTC 2024] Starting impl-alpha iteration 1\n",
+        )
+        .unwrap();
+        assert!(agent.read_log(&logs_dir, &LogScan::default()));
+        assert_eq!(agent.iterations, 1);
+        assert_eq!(agent.last_exit_code, None);
+
+        // A rotated log that's already longer than what was read is a new file
+        let rotated = dir.join("rotated.log");
+        fs::write(&rotated, SAMPLE_LOG.repeat(3)).unwrap();
+        fs::rename(&rotated, &path).unwrap();
+        assert!(agent.read_log(&logs_dir, &LogScan::default()));
+        assert_eq!(agent.iterations, 6);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn first_read_starts_near_the_end() {
+        let dir = std::env::temp_dir().join(format!("ampwatch-tail-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let logs_dir = dir.to_string_lossy().to_string();
+        let mut agent = Agent::new("impl-alpha", AgentType::Implementer, "abc123".to_string());
+        let path = agent.log_path(&logs_dir);
+
+        let mut log = "error: from long ago\n".to_string();
+        log.push_str(&"x".repeat(LOG_TAIL_BYTES as usize));
+        log.push_str(
+            "\n[Mon May  6 10:20:05 UTC 2024] Starting impl-alpha iteration 40\n\
+             [Mon May  6 10:40:05 UTC 2024] Starting impl-alpha iteration 41\n\
+             still working\n",
+        );
+        fs::write(&path, &log).unwrap();
+        assert!(agent.read_log(&logs_dir, &LogScan::default()));
+        assert_eq!(agent.iterations, 41);
+        assert_eq!(agent.last_activity, "still working");
+        assert!(!agent.has_errors());
+        assert_eq!(agent.log_offset, log.len() as u64);
+
+        fs::remove_dir_all(&dir).unwrap();
     }
 }
@@ -0,0 +1,43 @@
+use std::net::IpAddr;
+
+use clap::{Parser, Subcommand};
+
+/// Terminal dashboard for amptown agents and their PRs
+#[derive(Parser)]
+#[command(name = "ampwatch", version)]
+pub struct Cli {
+    /// Print environment diagnostics and offer to clean up orphaned sessions
+    #[arg(long)]
+    pub doctor: bool,
+
+    /// Print a one-shot status table of all instances and exit; same as
+    /// `ampwatch status`
+    #[arg(long)]
+    pub once: bool,
+
+    /// Seconds between automatic refreshes, overriding the config file
+    #[arg(long, value_name = "SECS")]
+    pub refresh_secs: Option<u64>,
+
+    /// Start focused on the instance with this id or repo name
+    #[arg(long, value_name = "ID")]
+    pub instance: Option<String>,
+
+    /// Serve a read-only HTML dashboard on this port
+    #[arg(long, value_name = "PORT")]
+    pub web: Option<u16>,
+
+    /// Address the --web dashboard listens on; 0.0.0.0 exposes it to the
+    /// network
+    #[arg(long, value_name = "ADDR", default_value = "127.0.0.1")]
+    pub web_bind: IpAddr,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Print a status table of all instances and exit
+    Status,
+}
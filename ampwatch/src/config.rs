@@ -0,0 +1,365 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+
+use crate::instance::{Instance, DEFAULT_MERGED_PR_LIMIT};
+use crate::pr::PullRequest;
+use crate::state::config_dir;
+
+/// Prompt used when `summary_prompt` isn't set
+const DEFAULT_SUMMARY_PROMPT: &str = "Summarize PR #{pr_number} in this repository. Include: what changed, why, and any concerns. Be concise.";
+
+/// Branch prefix agents push to when `pr_branch_pattern` isn't usable
+const DEFAULT_PR_BRANCH_PATTERN: &str = "{agent}/";
+
+/// Finds the model in an agent's log when `model_pattern` isn't usable.
+/// The first capture group is the model name.
+pub const DEFAULT_MODEL_PATTERN: &str = r"\bmodel[=:]\s*(\S+)";
+
+/// Without these amp can't tell which PR to summarize
+const REQUIRED_PROMPT_PLACEHOLDERS: &[&str] = &["{pr_number}"];
+
+/// User configuration loaded from `~/.config/ampwatch/config.toml`
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Ring the terminal bell on high-priority events
+    pub alert_bell: bool,
+    /// Shell command to run instead of the bell, e.g. `afplay /System/Library/Sounds/Ping.aiff`
+    pub alert_command: Option<String>,
+    /// Minimum seconds between two alerts
+    pub alert_debounce_secs: u64,
+    /// Ask before quitting while agents are running
+    pub confirm_quit: bool,
+    /// Show a desktop notification when a PR is merged or an agent stops
+    /// without a clean exit
+    pub desktop_notifications: bool,
+    /// Recently merged PRs to fetch, and how many more `M` loads. Closed
+    /// (unmerged) PRs are fetched up to the same limit.
+    pub merged_pr_limit: u32,
+    /// Prompt amp gets for PR summaries. Placeholders: `{pr_number}`
+    /// (required), `{repo}`, `{title}`, and `{branch}`.
+    pub summary_prompt: Option<String>,
+    /// Kill amp if a summary takes longer than this many seconds
+    pub summary_timeout_secs: u64,
+    /// How `m` merges PRs
+    pub merge_method: MergeMethod,
+    /// Fetch GitHub PRs over the GraphQL API with one shared client instead
+    /// of running gh per repo. Uses `GITHUB_TOKEN` or `gh auth token`, and
+    /// falls back to gh without a token or while rate limited.
+    pub github_api: bool,
+    /// Prefix of the branches an agent pushes, with `{agent}` standing for
+    /// its name. Links each PR to the agent that opened it.
+    pub pr_branch_pattern: String,
+    /// Programs to run, for binaries outside PATH or installed under
+    /// another name
+    pub amp_bin: String,
+    pub gh_bin: String,
+    pub glab_bin: String,
+    pub tmux_bin: String,
+    /// Regex flagging agent log lines as errors, on top of the built-in
+    /// `error`, `panic`, and `failed`
+    pub error_pattern: Option<String>,
+    /// Regex for the log line where an agent records its model; the first
+    /// capture group (or the whole match) is shown in the agent detail
+    pub model_pattern: String,
+    /// Extra globs for amptown log directories (`.../amptown-*/logs`),
+    /// searched alongside the built-in temp directories
+    pub log_patterns: Vec<String>,
+    /// Display names for instances, keyed by repo path or instance id
+    pub nicknames: HashMap<String, String>,
+    pub theme: ThemeConfig,
+    /// Use emoji like 💬 in the PR list; off falls back to ASCII (`c:3`)
+    pub emoji: bool,
+    /// Size the modal to its wrapped content instead of a fixed 80x60%
+    pub modal_auto_size: bool,
+    /// Wrap from the last item to the first (and back) when navigating
+    pub wrap_navigation: bool,
+    /// Items on the per-PR review checklist
+    pub checklist: Vec<String>,
+    /// Seconds between automatic refreshes
+    pub refresh_secs: u64,
+    /// Milliseconds between UI redraws (spinner speed, input latency)
+    pub tick_ms: u64,
+    /// Flag a running agent as stuck after this many seconds without log output
+    pub stuck_after_secs: u64,
+}
+
+/// Merge strategy passed to `gh pr merge`
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeMethod {
+    #[default]
+    Squash,
+    Merge,
+    Rebase,
+}
+
+impl MergeMethod {
+    pub fn flag(self) -> &'static str {
+        match self {
+            MergeMethod::Squash => "--squash",
+            MergeMethod::Merge => "--merge",
+            MergeMethod::Rebase => "--rebase",
+        }
+    }
+
+    pub fn describe(self) -> &'static str {
+        match self {
+            MergeMethod::Squash => "squashed into one commit",
+            MergeMethod::Merge => "merged with a merge commit",
+            MergeMethod::Rebase => "rebased onto the base branch",
+        }
+    }
+}
+
+/// Built-in palette the `[theme]` overrides apply on top of
+#[derive(Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemePreset {
+    #[default]
+    Dark,
+    Light,
+}
+
+/// Color overrides from the `[theme]` table. Values are ratatui color names
+/// (`green`, `lightred`), `#rrggbb` hex, or 256-color indices.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    /// `dark` or `light`
+    pub preset: ThemePreset,
+    /// Header, borders, and titles
+    pub accent: Option<String>,
+    pub highlight: Option<String>,
+    pub running: Option<String>,
+    pub stopped: Option<String>,
+    pub pr_open: Option<String>,
+    pub pr_merged: Option<String>,
+    pub pr_closed: Option<String>,
+    /// Used for any PR state other than OPEN, MERGED, or CLOSED
+    pub pr_unknown: Option<String>,
+    /// Use the 16-color palette even if the terminal looks capable of more
+    pub basic_colors: bool,
+}
+
+impl ThemeConfig {
+    /// Drop colors that don't parse, returning a message for each
+    fn validate(&mut self) -> Vec<String> {
+        let mut errors = Vec::new();
+        for (key, value) in [
+            ("accent", &mut self.accent),
+            ("highlight", &mut self.highlight),
+            ("running", &mut self.running),
+            ("stopped", &mut self.stopped),
+            ("pr_open", &mut self.pr_open),
+            ("pr_merged", &mut self.pr_merged),
+            ("pr_closed", &mut self.pr_closed),
+            ("pr_unknown", &mut self.pr_unknown),
+        ] {
+            if let Some(color) = value {
+                if Color::from_str(color).is_err() {
+                    errors.push(format!("theme.{}: unknown color '{}'", key, color));
+                    *value = None;
+                }
+            }
+        }
+        errors
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            alert_bell: false,
+            alert_command: None,
+            alert_debounce_secs: 60,
+            confirm_quit: true,
+            desktop_notifications: false,
+            summary_prompt: None,
+            summary_timeout_secs: 120,
+            merge_method: MergeMethod::default(),
+            github_api: false,
+            pr_branch_pattern: DEFAULT_PR_BRANCH_PATTERN.to_string(),
+            amp_bin: "amp".to_string(),
+            gh_bin: "gh".to_string(),
+            glab_bin: "glab".to_string(),
+            tmux_bin: "tmux".to_string(),
+            merged_pr_limit: DEFAULT_MERGED_PR_LIMIT,
+            log_patterns: Vec::new(),
+            error_pattern: None,
+            model_pattern: DEFAULT_MODEL_PATTERN.to_string(),
+            nicknames: HashMap::new(),
+            theme: ThemeConfig::default(),
+            emoji: true,
+            modal_auto_size: false,
+            wrap_navigation: true,
+            checklist: vec![
+                "tests pass".to_string(),
+                "no secrets".to_string(),
+                "docs updated".to_string(),
+            ],
+            refresh_secs: 5,
+            tick_ms: 200,
+            stuck_after_secs: 300,
+        }
+    }
+}
+
+impl Config {
+    pub fn path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join("config.toml"))
+    }
+
+    pub fn refresh_interval(&self) -> Duration {
+        Duration::from_secs(self.refresh_secs.max(1))
+    }
+
+    pub fn tick_interval(&self) -> Duration {
+        Duration::from_millis(self.tick_ms.max(10))
+    }
+
+    pub fn summary_timeout(&self) -> Duration {
+        Duration::from_secs(self.summary_timeout_secs.max(1))
+    }
+
+    pub fn stuck_threshold(&self) -> Duration {
+        Duration::from_secs(self.stuck_after_secs)
+    }
+
+    /// The summary prompt for `pr` in `repo`, from `summary_prompt` or the
+    /// built-in default
+    pub fn render_summary_prompt(&self, pr: &PullRequest, repo: &str) -> String {
+        self.summary_prompt
+            .as_deref()
+            .unwrap_or(DEFAULT_SUMMARY_PROMPT)
+            .replace("{pr_number}", &pr.number.to_string())
+            .replace("{repo}", repo)
+            .replace("{title}", &pr.title)
+            .replace("{branch}", &pr.head_ref_name)
+    }
+
+    /// Drop a `summary_prompt` missing a required placeholder, returning a
+    /// message for each problem
+    fn validate_summary_prompt(&mut self) -> Vec<String> {
+        let Some(prompt) = &self.summary_prompt else {
+            return Vec::new();
+        };
+        let missing: Vec<&str> = REQUIRED_PROMPT_PLACEHOLDERS
+            .iter()
+            .copied()
+            .filter(|p| !prompt.contains(p))
+            .collect();
+        if missing.is_empty() {
+            return Vec::new();
+        }
+        self.summary_prompt = None;
+        vec![format!(
+            "summary_prompt: missing {}; using the default prompt",
+            missing.join(", ")
+        )]
+    }
+
+    /// Reset a `pr_branch_pattern` without `{agent}`, which would credit
+    /// every PR to the first agent
+    fn validate_pr_branch_pattern(&mut self) -> Vec<String> {
+        if self.pr_branch_pattern.contains("{agent}") {
+            return Vec::new();
+        }
+        self.pr_branch_pattern = DEFAULT_PR_BRANCH_PATTERN.to_string();
+        vec![format!(
+            "pr_branch_pattern: missing {{agent}}; using '{}'",
+            DEFAULT_PR_BRANCH_PATTERN
+        )]
+    }
+
+    /// Drop `log_patterns` that aren't valid globs
+    fn validate_log_patterns(&mut self) -> Vec<String> {
+        let mut errors = Vec::new();
+        self.log_patterns
+            .retain(|pattern| match glob::Pattern::new(pattern) {
+                Ok(_) => true,
+                Err(e) => {
+                    errors.push(format!("log_patterns: invalid glob '{}': {}", pattern, e));
+                    false
+                }
+            });
+        errors
+    }
+
+    /// Drop an `error_pattern` that isn't a valid regex
+    fn validate_error_pattern(&mut self) -> Vec<String> {
+        let Some(pattern) = &self.error_pattern else {
+            return Vec::new();
+        };
+        let Err(e) = regex::Regex::new(pattern) else {
+            return Vec::new();
+        };
+        let error = format!("error_pattern: invalid regex '{}': {}", pattern, e);
+        self.error_pattern = None;
+        vec![error]
+    }
+
+    /// Reset a `model_pattern` that isn't a valid regex
+    fn validate_model_pattern(&mut self) -> Vec<String> {
+        let Err(e) = regex::Regex::new(&self.model_pattern) else {
+            return Vec::new();
+        };
+        let error = format!(
+            "model_pattern: invalid regex '{}': {}; using the default",
+            self.model_pattern, e
+        );
+        self.model_pattern = DEFAULT_MODEL_PATTERN.to_string();
+        vec![error]
+    }
+
+    /// Nickname for an instance, falling back to its repo name
+    pub fn instance_label(&self, instance: &Instance) -> String {
+        instance
+            .repo_path
+            .as_ref()
+            .and_then(|path| self.nicknames.get(path))
+            .or_else(|| self.nicknames.get(&instance.id))
+            .cloned()
+            .unwrap_or_else(|| instance.repo_name())
+    }
+
+    /// Load the config file, falling back to defaults if it is missing.
+    /// A malformed file prints a warning to stderr, so call this before
+    /// the terminal enters raw mode.
+    pub fn load() -> Self {
+        let Some(path) = Self::path() else {
+            return Self::default();
+        };
+        let Ok(content) = fs::read_to_string(&path) else {
+            return Self::default();
+        };
+        match toml::from_str::<Self>(&content) {
+            Ok(mut config) => {
+                let mut errors = config.theme.validate();
+                errors.extend(config.validate_summary_prompt());
+                errors.extend(config.validate_pr_branch_pattern());
+                errors.extend(config.validate_log_patterns());
+                errors.extend(config.validate_error_pattern());
+                errors.extend(config.validate_model_pattern());
+                for error in errors {
+                    eprintln!("ampwatch: {}: {}", path.display(), error);
+                }
+                config
+            }
+            Err(e) => {
+                eprintln!(
+                    "ampwatch: ignoring malformed config {}: {}",
+                    path.display(),
+                    e
+                );
+                Self::default()
+            }
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use std::io::{self, Write};
+use std::process::Command;
+
+use crate::agent::{LogScan, StatusSource};
+use crate::config::Config;
+use crate::instance::{
+    discover_instances, kill_session, log_roots, orphaned_sessions, Instance, LOG_SEARCH_DEPTH,
+};
+use crate::runner::SystemRunner;
+
+/// Print environment diagnostics for `ampwatch --doctor`
+pub fn run(config: &Config) {
+    println!("ampwatch doctor\n");
+    let runner = SystemRunner::new(config);
+
+    println!("Binaries:");
+    for bin in [&config.tmux_bin, &config.gh_bin, &config.amp_bin] {
+        match Command::new(bin).arg("--version").output() {
+            Ok(_) => println!("  ✓ {}", bin),
+            Err(e) => println!("  ✗ {} ({})", bin, e),
+        }
+    }
+
+    println!(
+        "\nLog roots (searched {} levels deep for amptown-*/logs):",
+        LOG_SEARCH_DEPTH
+    );
+    for root in log_roots() {
+        println!("  ✓ {}", root.display());
+    }
+    for pattern in &config.log_patterns {
+        println!("  + {} (log_patterns)", pattern);
+    }
+
+    let discovery = discover_instances(&runner, &config.log_patterns);
+
+    if !discovery.warnings.is_empty() {
+        println!("\nWarnings:");
+        for warning in &discovery.warnings {
+            println!("  ! {}", warning);
+        }
+    }
+
+    println!("\nInstances ({}):", discovery.instances.len());
+    let mut instances: Vec<Instance> = discovery.instances.into_values().collect();
+    instances.sort_by(|a, b| a.id.cmp(&b.id));
+    for instance in &mut instances {
+        instance.refresh(&runner, None, &LogScan::new(config));
+        println!(
+            "  {} ({}) running {}/{}",
+            instance.repo_name(),
+            instance.id,
+            instance.running_agent_count(),
+            instance.agents.len()
+        );
+        println!(
+            "    repo: {}",
+            instance.repo_path.as_deref().unwrap_or("unknown")
+        );
+        println!(
+            "    logs: {}",
+            instance.logs_dir.as_deref().unwrap_or("not found")
+        );
+        for agent in &instance.agents {
+            let source = match agent.status_source {
+                Some(StatusSource::StatusFile) => "status file",
+                Some(StatusSource::Log) => "log",
+                None => "no status",
+            };
+            println!("    {:<16} {}", agent.name, source);
+        }
+        for warning in &instance.log_warnings {
+            println!("    ! {}", warning);
+        }
+    }
+
+    let orphans = orphaned_sessions(&discovery.sessions, &instances);
+    if orphans.is_empty() {
+        return;
+    }
+    println!("\nOrphaned tmux sessions ({}):", orphans.len());
+    for session in &orphans {
+        println!("  {}", session);
+    }
+
+    print!("\nKill these sessions? [y/N] ");
+    let _ = io::stdout().flush();
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer).is_ok() && answer.trim().eq_ignore_ascii_case("y") {
+        for session in &orphans {
+            if kill_session(&runner, session) {
+                println!("  killed {}", session);
+            } else {
+                println!("  failed to kill {}", session);
+            }
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use crate::agent::AgentStatus;
+use crate::instance::Instance;
+
+/// A notable change between two refresh snapshots
+pub enum Event {
+    /// A running agent stopped without a clean exit
+    AgentStopped {
+        repo: String,
+        agent: String,
+        exit_code: Option<i32>,
+    },
+    PrConflicting {
+        repo: String,
+        number: u32,
+    },
+    AllAgentsStopped {
+        repo: String,
+    },
+    PrMerged {
+        repo: String,
+        number: u32,
+        title: String,
+    },
+}
+
+impl Event {
+    pub fn describe(&self) -> String {
+        match self {
+            Event::AgentStopped {
+                repo,
+                agent,
+                exit_code: Some(code),
+            } => format!("{}: agent {} stopped (exit code {})", repo, agent, code),
+            Event::AgentStopped { repo, agent, .. } => {
+                format!("{}: agent {} stopped", repo, agent)
+            }
+            Event::PrConflicting { repo, number } => {
+                format!("{}: PR #{} has merge conflicts", repo, number)
+            }
+            Event::AllAgentsStopped { repo } => format!("{}: all agents stopped", repo),
+            Event::PrMerged {
+                repo,
+                number,
+                title,
+            } => format!("{}: PR #{} merged: {}", repo, number, title),
+        }
+    }
+
+    /// Whether the event rings the bell; merges are good news and only
+    /// produce desktop notifications
+    pub fn is_alert(&self) -> bool {
+        !matches!(self, Event::PrMerged { .. })
+    }
+}
+
+/// Compare the previous and next snapshots. Instances absent from `prev` are
+/// skipped so the first refresh doesn't report everything as new.
+pub fn diff(prev: &[Instance], next: &[Instance]) -> Vec<Event> {
+    let mut events = Vec::new();
+
+    for new in next {
+        let Some(old) = prev.iter().find(|i| i.id == new.id) else {
+            continue;
+        };
+        let repo = new.repo_name();
+
+        for agent in &new.agents {
+            let was_running = old
+                .agents
+                .iter()
+                .any(|a| a.name == agent.name && a.is_running);
+            // A clean exit marker (or status file) means it was shut down on purpose
+            if was_running && !agent.is_running && agent.status() != AgentStatus::Completed {
+                events.push(Event::AgentStopped {
+                    repo: repo.clone(),
+                    agent: agent.name.clone(),
+                    exit_code: agent.last_exit_code,
+                });
+            }
+        }
+
+        for pr in new.open_prs.iter().filter(|pr| pr.is_conflicting()) {
+            let was_conflicting = old
+                .open_prs
+                .iter()
+                .any(|p| p.number == pr.number && p.is_conflicting());
+            if !was_conflicting {
+                events.push(Event::PrConflicting {
+                    repo: repo.clone(),
+                    number: pr.number,
+                });
+            }
+        }
+
+        // An unloaded list would make every merged PR look new
+        if old.closed_prs_loaded && new.closed_prs_loaded {
+            for pr in &new.merged_prs {
+                if !old.merged_prs.iter().any(|p| p.number == pr.number) {
+                    events.push(Event::PrMerged {
+                        repo: repo.clone(),
+                        number: pr.number,
+                        title: pr.title.clone(),
+                    });
+                }
+            }
+        }
+
+        if old.running_agent_count() > 0 && new.running_agent_count() == 0 {
+            events.push(Event::AllAgentsStopped { repo });
+        }
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pr::PullRequest;
+
+    fn merged(number: u32) -> PullRequest {
+        serde_json::from_value(serde_json::json!({
+            "number": number,
+            "title": format!("PR {}", number),
+            "state": "MERGED",
+            "author": {"login": "impl-bot"},
+            "createdAt": "2024-05-01T12:00:00Z",
+            "headRefName": "branch",
+        }))
+        .unwrap()
+    }
+
+    fn instance(merged_prs: &[u32], loaded: bool) -> Instance {
+        let mut instance = Instance::new("deadbeef".to_string());
+        instance.merged_prs = merged_prs.iter().map(|&n| merged(n)).collect();
+        instance.closed_prs_loaded = loaded;
+        instance
+    }
+
+    fn merged_numbers(events: &[Event]) -> Vec<u32> {
+        events
+            .iter()
+            .filter_map(|e| match e {
+                Event::PrMerged { number, .. } => Some(*number),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reports_only_newly_merged_prs() {
+        let events = diff(&[instance(&[1, 2], true)], &[instance(&[3, 1, 2], true)]);
+        assert_eq!(merged_numbers(&events), vec![3]);
+    }
+
+    #[test]
+    fn reports_agents_stopping_without_a_clean_exit() {
+        let mut old = instance(&[], true);
+        for agent in &mut old.agents {
+            agent.is_running = true;
+        }
+        let mut new = instance(&[], true);
+        // impl-alpha exited cleanly; the rest died mid-iteration
+        for agent in &mut new.agents {
+            agent.iterations = 2;
+        }
+        new.agents[3].last_exit_code = Some(0);
+
+        let stopped: Vec<String> = diff(&[old], &[new])
+            .iter()
+            .filter_map(|e| match e {
+                Event::AgentStopped { agent, .. } => Some(agent.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(stopped.len(), 5);
+        assert!(!stopped.contains(&"impl-alpha".to_string()));
+    }
+
+    #[test]
+    fn first_load_of_merged_prs_is_not_news() {
+        assert!(diff(&[], &[instance(&[1, 2], true)]).is_empty());
+        assert!(diff(&[instance(&[], false)], &[instance(&[1, 2], true)]).is_empty());
+    }
+}
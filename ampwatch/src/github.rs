@@ -0,0 +1,343 @@
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, bail, Result};
+use reqwest::blocking::{Client, Response};
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+
+use crate::pr::PullRequest;
+use crate::runner::CommandRunner;
+
+const GRAPHQL_URL: &str = "https://api.github.com/graphql";
+
+/// Attempts per fetch before giving up on a rate-limited or failing API
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Longest rate-limit wait slept through inside a refresh; longer ones
+/// fall back to gh until the limit resets
+const MAX_INLINE_WAIT: Duration = Duration::from_secs(10);
+
+/// Backoff for a GraphQL rate limit that doesn't say when it resets
+const RATE_LIMIT_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Open PRs, recently merged and closed PRs, and all-time totals in one
+/// round trip. Fields mirror `PR_JSON_FIELDS`.
+const PRS_QUERY: &str = "query($owner: String!, $name: String!, $limit: Int!) {
+  repository(owner: $owner, name: $name) {
+    open: pullRequests(states: OPEN, first: 100, orderBy: {field: CREATED_AT, direction: DESC}) { nodes { ...pr } }
+    merged: pullRequests(states: MERGED, first: $limit, orderBy: {field: CREATED_AT, direction: DESC}) { totalCount nodes { ...pr } }
+    closed: pullRequests(states: CLOSED, first: $limit, orderBy: {field: CREATED_AT, direction: DESC}) { totalCount nodes { ...pr } }
+  }
+}
+fragment pr on PullRequest {
+  number title state author { login } createdAt headRefName headRefOid mergeable url
+  reviewDecision additions deletions changedFiles isDraft comments { totalCount }
+  commits(last: 1) { nodes { commit { statusCheckRollup { contexts(first: 100) { nodes {
+    ... on CheckRun { status conclusion }
+    ... on StatusContext { state }
+  } } } } } }
+}";
+
+/// PRs of one repo as fetched by `GitHubApi::fetch_prs`
+pub struct ApiPrs {
+    pub open: Vec<PullRequest>,
+    pub merged: Vec<PullRequest>,
+    pub closed: Vec<PullRequest>,
+    pub merged_total: Option<u32>,
+    pub closed_total: Option<u32>,
+}
+
+/// Talks to the GitHub GraphQL API directly, for `github_api = true`.
+/// Cloning shares the HTTP client, token, and rate-limit state, so one
+/// instance serves every repo and refresh.
+#[derive(Clone)]
+pub struct GitHubApi {
+    client: Client,
+    /// From `GITHUB_TOKEN` or `gh auth token`; looked up again each refresh
+    /// until one is found
+    token: Arc<Mutex<Option<String>>>,
+    /// Primary rate limit hit; gh is used until this passes
+    limited_until: Arc<Mutex<Option<Instant>>>,
+}
+
+/// GraphQL reported `RATE_LIMITED`, which comes back with a 200 status
+#[derive(Debug)]
+struct RateLimited;
+
+impl fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("GraphQL rate limit exceeded")
+    }
+}
+
+impl std::error::Error for RateLimited {}
+
+impl GitHubApi {
+    /// Fails if the HTTP client can't be built, e.g. without TLS roots
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            client: Client::builder()
+                .user_agent(concat!("ampwatch/", env!("CARGO_PKG_VERSION")))
+                .timeout(Duration::from_secs(30))
+                .build()?,
+            token: Arc::default(),
+            limited_until: Arc::default(),
+        })
+    }
+
+    /// Find a token if there isn't one yet, returning whether there is
+    pub fn ensure_token(&self, runner: &dyn CommandRunner) -> bool {
+        let mut token = self.token.lock().unwrap();
+        if token.is_none() {
+            *token = std::env::var("GITHUB_TOKEN")
+                .ok()
+                .or_else(|| {
+                    let output = runner.run("gh", &["auth", "token"], None).ok()?;
+                    output
+                        .status
+                        .success()
+                        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+                })
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty());
+        }
+        token.is_some()
+    }
+
+    /// Whether a primary rate limit is still in effect
+    pub fn rate_limited(&self) -> bool {
+        self.limited_until
+            .lock()
+            .unwrap()
+            .is_some_and(|until| Instant::now() < until)
+    }
+
+    /// Fetch `owner/name`'s PRs, keeping `limit` recently merged ones and
+    /// as many closed ones. Short rate-limit waits and server errors are
+    /// retried with backoff; anything else is returned for the caller to
+    /// fall back on gh.
+    pub fn fetch_prs(&self, owner: &str, name: &str, limit: u32) -> Result<ApiPrs> {
+        if self.rate_limited() {
+            bail!("rate limited; using gh until the limit resets");
+        }
+        let token = self
+            .token
+            .lock()
+            .unwrap()
+            .clone()
+            .ok_or_else(|| anyhow!("no GitHub token"))?;
+        let body = json!({
+            "query": PRS_QUERY,
+            "variables": {"owner": owner, "name": name, "limit": limit.min(100)},
+        });
+
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let response = self
+                .client
+                .post(GRAPHQL_URL)
+                .bearer_auth(&token)
+                .json(&body)
+                .send()?;
+            let status = response.status();
+            if status.is_success() {
+                let reset = until_reset(response.headers());
+                let prs = parse_prs(&response.bytes()?);
+                if prs.as_ref().is_err_and(|e| e.is::<RateLimited>()) {
+                    let wait = reset.unwrap_or(RATE_LIMIT_BACKOFF);
+                    *self.limited_until.lock().unwrap() = Some(Instant::now() + wait);
+                    bail!("rate limited for {}s", wait.as_secs());
+                }
+                return prs;
+            }
+
+            let wait = retry_delay(status, response.headers(), attempt);
+            match wait {
+                Some(wait) if attempt < MAX_ATTEMPTS && wait <= MAX_INLINE_WAIT => {
+                    thread::sleep(wait);
+                }
+                Some(wait) if is_rate_limit(status) => {
+                    *self.limited_until.lock().unwrap() = Some(Instant::now() + wait);
+                    bail!("rate limited for {}s", wait.as_secs());
+                }
+                _ => bail!("{}", error_message(response)),
+            }
+        }
+    }
+}
+
+fn is_rate_limit(status: StatusCode) -> bool {
+    status == StatusCode::FORBIDDEN || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// How long to wait before retrying a failed request, or `None` if it
+/// shouldn't be retried. Rate limits say when they reset; server errors
+/// back off exponentially.
+fn retry_delay(status: StatusCode, headers: &HeaderMap, attempt: u32) -> Option<Duration> {
+    let header = |name: &str| -> Option<u64> { headers.get(name)?.to_str().ok()?.parse().ok() };
+    let backoff = Duration::from_secs(1 << attempt.min(6));
+    if is_rate_limit(status) {
+        if let Some(secs) = header("retry-after") {
+            return Some(Duration::from_secs(secs));
+        }
+        if header("x-ratelimit-remaining") == Some(0) {
+            return until_reset(headers);
+        }
+        // A 403 without rate-limit headers is a permissions problem
+        (status == StatusCode::TOO_MANY_REQUESTS).then_some(backoff)
+    } else {
+        status.is_server_error().then_some(backoff)
+    }
+}
+
+/// Time left until the `x-ratelimit-reset` header's epoch second
+fn until_reset(headers: &HeaderMap) -> Option<Duration> {
+    let reset: u64 = headers
+        .get("x-ratelimit-reset")?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+    Some(Duration::from_secs(reset.saturating_sub(now).max(1)))
+}
+
+fn error_message(response: Response) -> String {
+    let status = response.status();
+    let body: Value = response.json().unwrap_or_default();
+    match body["message"].as_str() {
+        Some(message) => format!("{}: {}", status, message),
+        None => status.to_string(),
+    }
+}
+
+/// Parse a `PRS_QUERY` response. Nodes are reshaped into gh's `--json`
+/// layout so `PullRequest` deserializes them the same way.
+fn parse_prs(body: &[u8]) -> Result<ApiPrs> {
+    let body: Value = serde_json::from_slice(body)?;
+    let mut errors = body["errors"].as_array().into_iter().flatten();
+    if errors.any(|e| e["type"] == "RATE_LIMITED") {
+        return Err(RateLimited.into());
+    }
+    if let Some(error) = body["errors"][0]["message"].as_str() {
+        bail!("{}", error);
+    }
+    let repo = &body["data"]["repository"];
+    if repo.is_null() {
+        bail!("repository not found");
+    }
+
+    let prs = |key: &str| -> Vec<PullRequest> {
+        repo[key]["nodes"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|node| {
+                let mut node = node.clone();
+                node["statusCheckRollup"] = node
+                    .pointer("/commits/nodes/0/commit/statusCheckRollup/contexts/nodes")
+                    .cloned()
+                    .unwrap_or_default();
+                node["comments"] = node["comments"]["totalCount"].clone();
+                // Deleted accounts come back as null
+                if node["author"].is_null() {
+                    node["author"] = json!({"login": "ghost"});
+                }
+                serde_json::from_value(node).ok()
+            })
+            .collect()
+    };
+    let total = |key: &str| repo[key]["totalCount"].as_u64().map(|n| n as u32);
+
+    Ok(ApiPrs {
+        open: prs("open"),
+        merged: prs("merged"),
+        closed: prs("closed"),
+        merged_total: total("merged"),
+        closed_total: total("closed"),
+    })
+}
+
+/// `owner` and repo name from a GitHub remote URL, SSH or HTTPS
+pub fn repo_slug(url: &str) -> Option<(String, String)> {
+    let path = url
+        .split_once("github.com")?
+        .1
+        .trim_start_matches([':', '/'])
+        .trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    let (owner, name) = path.split_once('/')?;
+    (!owner.is_empty() && !name.is_empty() && !name.contains('/'))
+        .then(|| (owner.to_string(), name.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pr::CheckStatus;
+
+    #[test]
+    fn parses_slug_from_remotes() {
+        let slug = Some(("o".to_string(), "r".to_string()));
+        assert_eq!(repo_slug("git@github.com:o/r.git"), slug);
+        assert_eq!(repo_slug("https://github.com/o/r"), slug);
+        assert_eq!(repo_slug("ssh://git@github.com/o/r.git"), slug);
+        assert_eq!(repo_slug("git@gitlab.com:o/r.git"), None);
+    }
+
+    #[test]
+    fn parses_graphql_prs() {
+        let body = br#"{"data": {"repository": {
+            "open": {"nodes": [{"number": 12, "title": "Add parser", "state": "OPEN",
+                "author": null, "createdAt": "2024-05-01T12:00:00Z", "headRefName": "impl-alpha/parser",
+                "headRefOid": "abc", "mergeable": "MERGEABLE", "url": "", "reviewDecision": null,
+                "additions": 3, "deletions": 1, "changedFiles": 1, "isDraft": false,
+                "comments": {"totalCount": 2},
+                "commits": {"nodes": [{"commit": {"statusCheckRollup": {"contexts": {"nodes": [
+                    {"status": "COMPLETED", "conclusion": "FAILURE"}]}}}}]}}]},
+            "merged": {"totalCount": 57, "nodes": [
+                {"number": 10, "title": "Old fix", "state": "MERGED", "author": {"login": "impl-bot"},
+                 "createdAt": "2024-04-01T12:00:00Z", "headRefName": "fix", "comments": {"totalCount": 0},
+                 "commits": {"nodes": [{"commit": {"statusCheckRollup": null}}]}}]},
+            "closed": {"totalCount": 3, "nodes": []}}}}"#;
+        let prs = parse_prs(body).unwrap();
+
+        let pr = &prs.open[0];
+        assert_eq!(pr.author.login, "ghost");
+        assert_eq!(pr.comments, 2);
+        assert_eq!(pr.checks, CheckStatus::Failing);
+        assert_eq!(prs.merged[0].checks, CheckStatus::None);
+        assert!(prs.closed.is_empty());
+        assert_eq!((prs.merged_total, prs.closed_total), (Some(57), Some(3)));
+    }
+
+    #[test]
+    fn detects_graphql_rate_limit() {
+        let body = br#"{"data": null, "errors": [{"type": "RATE_LIMITED",
+            "message": "API rate limit exceeded for user ID 1."}]}"#;
+        assert!(parse_prs(body).is_err_and(|e| e.is::<RateLimited>()));
+
+        let body = br#"{"errors": [{"type": "NOT_FOUND", "message": "Could not resolve"}]}"#;
+        assert!(parse_prs(body).is_err_and(|e| !e.is::<RateLimited>()));
+    }
+
+    #[test]
+    fn backs_off_on_rate_limits() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_delay(StatusCode::FORBIDDEN, &headers, 1), None);
+        assert_eq!(
+            retry_delay(StatusCode::BAD_GATEWAY, &headers, 2),
+            Some(Duration::from_secs(4))
+        );
+        headers.insert("retry-after", "30".parse().unwrap());
+        assert_eq!(
+            retry_delay(StatusCode::FORBIDDEN, &headers, 1),
+            Some(Duration::from_secs(30))
+        );
+    }
+}
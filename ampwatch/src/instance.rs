@@ -1,18 +1,70 @@
 use std::collections::HashMap;
-use std::process::Command;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
-use crate::agent::{Agent, AgentType};
+use serde::Serialize;
+
+use crate::agent::{Agent, AgentType, LogScan};
+use crate::github::{self, GitHubApi};
 use crate::pr::PullRequest;
+use crate::provider::Provider;
+use crate::runner::CommandRunner;
 
-/// An amptown instance (one per repository)
+/// A record of an external command invocation, kept for debugging
 #[derive(Clone)]
+pub struct CommandLog {
+    pub command: String,
+    pub status: String,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// An amptown instance (one per repository)
+#[derive(Clone, Serialize)]
 pub struct Instance {
     pub id: String,
     pub repo_path: Option<String>,
     pub logs_dir: Option<String>,
+    /// When the log directory was created, i.e. roughly when amptown started
+    pub created_at: Option<SystemTime>,
     pub agents: Vec<Agent>,
     pub open_prs: Vec<PullRequest>,
+    pub merged_prs: Vec<PullRequest>,
+    /// Closed without merging
     pub closed_prs: Vec<PullRequest>,
+    /// `merged_prs` and `closed_prs` came from a successful gh call
+    pub closed_prs_loaded: bool,
+    /// Recently merged PRs to fetch; as many closed ones are fetched too
+    pub merged_pr_limit: u32,
+    /// All-time counts from GitHub, if the query succeeded
+    pub merged_total: Option<u32>,
+    pub closed_total: Option<u32>,
+    /// Newest merged and closed PR numbers when the totals were counted;
+    /// `refresh_prs` only counts again once these change
+    #[serde(skip)]
+    totals_counted_at: Option<(Option<u32>, Option<u32>)>,
+    /// Checked-out branch of `repo_path`, if it is a git repository
+    pub branch: Option<String>,
+    /// `repo_path` has uncommitted or untracked changes
+    pub dirty: bool,
+    /// URL of the `origin` remote
+    pub remote_url: Option<String>,
+    /// Forge PRs are fetched from, going by the `origin` remote
+    pub provider: Provider,
+    #[serde(skip)]
+    pub gh_log: Vec<CommandLog>,
+    /// A background refresh hasn't reached this instance yet
+    #[serde(skip)]
+    pub refreshing: bool,
+    /// Agents sharing a log file, found by `refresh_agents`
+    pub log_warnings: Vec<String>,
+    /// The last call failed because the provider's CLI (gh or glab) isn't
+    /// installed
+    pub gh_missing: bool,
+    /// CLI authentication failure from this refresh, shown in place of the PR list
+    pub last_error: Option<String>,
 }
 
 impl Instance {
@@ -21,6 +73,7 @@ impl Instance {
             id: id.clone(),
             repo_path: None,
             logs_dir: None,
+            created_at: None,
             agents: vec![
                 Agent::new("reviewer-alpha", AgentType::Reviewer, id.clone()),
                 Agent::new("reviewer-beta", AgentType::Reviewer, id.clone()),
@@ -30,29 +83,84 @@ impl Instance {
                 Agent::new("impl-gamma", AgentType::Implementer, id.clone()),
             ],
             open_prs: Vec::new(),
+            merged_prs: Vec::new(),
             closed_prs: Vec::new(),
+            closed_prs_loaded: false,
+            merged_pr_limit: DEFAULT_MERGED_PR_LIMIT,
+            merged_total: None,
+            closed_total: None,
+            totals_counted_at: None,
+            branch: None,
+            dirty: false,
+            remote_url: None,
+            provider: Provider::default(),
+            gh_log: Vec::new(),
+            refreshing: false,
+            log_warnings: Vec::new(),
+            gh_missing: false,
+            last_error: None,
         }
     }
 
-    pub fn refresh(&mut self) {
-        self.find_repo_path();
-        self.refresh_agents();
-        self.refresh_prs();
+    /// Rescan agents and the repo. PRs come from `github` when it's given
+    /// and the repo is on GitHub, with the provider's CLI as the fallback.
+    pub fn refresh(
+        &mut self,
+        runner: &dyn CommandRunner,
+        github: Option<&GitHubApi>,
+        log_scan: &LogScan,
+    ) {
+        self.find_repo_path(runner);
+        self.refresh_agents(runner, log_scan);
+        self.refresh_git(runner);
+        if !github.is_some_and(|api| self.refresh_prs_from_api(api)) {
+            self.refresh_prs(runner);
+        }
+    }
+
+    /// Carry per-agent log scan state, the PRs, and their totals over from
+    /// the previous snapshot. Agents are matched by session, not just name:
+    /// `merge_duplicates` moves agents between instances, and each one's
+    /// scan state belongs to its own instance's log.
+    pub fn carry_over(&mut self, previous: &[Instance]) {
+        for agent in &mut self.agents {
+            let old = previous
+                .iter()
+                .flat_map(|p| &p.agents)
+                .find(|a| a.instance_id == agent.instance_id && a.name == agent.name);
+            if let Some(old) = old {
+                agent.carry_over(old);
+            }
+        }
+        let Some(prev) = previous.iter().find(|p| p.id == self.id) else {
+            return;
+        };
+        // Kept when a gh call fails, rather than emptying the lists and
+        // raising their events again once gh recovers
+        self.open_prs = prev.open_prs.clone();
+        self.merged_prs = prev.merged_prs.clone();
+        self.closed_prs = prev.closed_prs.clone();
+        self.closed_prs_loaded = prev.closed_prs_loaded;
+        self.merged_total = prev.merged_total;
+        self.closed_total = prev.closed_total;
+        self.totals_counted_at = prev.totals_counted_at;
     }
 
-    fn find_repo_path(&mut self) {
+    fn find_repo_path(&mut self, runner: &dyn CommandRunner) {
         // Get repo path from any running agent's tmux session
         for agent in &self.agents {
             let session_name = format!("amptown-{}-{}", self.id, agent.name);
-            let output = Command::new("tmux")
-                .args([
+            let output = runner.run(
+                "tmux",
+                &[
                     "display-message",
                     "-t",
                     &session_name,
                     "-p",
                     "#{pane_current_path}",
-                ])
-                .output();
+                ],
+                None,
+            );
 
             if let Ok(output) = output {
                 if output.status.success() {
@@ -66,62 +174,238 @@ impl Instance {
         }
     }
 
-    fn refresh_agents(&mut self) {
+    fn refresh_agents(&mut self, runner: &dyn CommandRunner, log_scan: &LogScan) {
         for agent in &mut self.agents {
-            agent.refresh(&self.logs_dir);
+            agent.refresh(&self.logs_dir, runner, log_scan);
         }
+        self.check_shared_logs();
     }
 
-    fn refresh_prs(&mut self) {
-        let Some(repo_path) = &self.repo_path else {
+    /// Warn when agents resolve to the same log file (e.g. via symlinks) or
+    /// a log contains another agent's markers; either inflates iteration counts
+    fn check_shared_logs(&mut self) {
+        self.log_warnings.clear();
+        let Some(logs_dir) = &self.logs_dir else {
             return;
         };
 
-        // Get open PRs - force fresh data with --no-cache if available
-        if let Ok(output) = Command::new("gh")
-            .args([
-                "pr",
-                "list",
-                "--json",
-                "number,title,state,author,createdAt,headRefName",
-            ])
-            .current_dir(repo_path)
-            .output()
-        {
-            if output.status.success() {
-                if let Ok(prs) = serde_json::from_slice::<Vec<PullRequest>>(&output.stdout) {
-                    self.open_prs = prs;
-                }
+        let mut by_path: HashMap<PathBuf, Vec<&str>> = HashMap::new();
+        for agent in &self.agents {
+            if let Ok(path) = fs::canonicalize(agent.log_path(logs_dir)) {
+                by_path.entry(path).or_default().push(&agent.name);
             }
         }
+        let mut shared: Vec<_> = by_path.into_iter().filter(|(_, a)| a.len() > 1).collect();
+        shared.sort();
+        for (path, names) in shared {
+            self.log_warnings
+                .push(format!("{} share log {}", names.join(", "), path.display()));
+        }
 
-        // Get closed/merged PRs
-        if let Ok(output) = Command::new("gh")
-            .args([
-                "pr",
-                "list",
-                "--state",
-                "merged",
-                "--limit",
-                "10",
-                "--json",
-                "number,title,state,author,createdAt,headRefName",
-            ])
-            .current_dir(repo_path)
-            .output()
-        {
-            if output.status.success() {
-                if let Ok(prs) = serde_json::from_slice::<Vec<PullRequest>>(&output.stdout) {
-                    self.closed_prs = prs;
-                }
+        for agent in &self.agents {
+            if !agent.foreign_log_agents.is_empty() {
+                self.log_warnings.push(format!(
+                    "{}.log contains iterations from {}",
+                    agent.name,
+                    agent.foreign_log_agents.join(", ")
+                ));
             }
         }
     }
 
+    /// Read the repo's branch, whether its working tree is clean, and which
+    /// forge its `origin` points at. Paths that aren't git repositories
+    /// leave these unset.
+    fn refresh_git(&mut self, runner: &dyn CommandRunner) {
+        let Some(repo_path) = &self.repo_path else {
+            return;
+        };
+        let git = |args: &[&str]| {
+            runner
+                .run("git", args, Some(Path::new(repo_path)))
+                .ok()
+                .filter(|output| output.status.success())
+                .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+        };
+        self.branch = git(&["rev-parse", "--abbrev-ref", "HEAD"])
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+        self.dirty = self.branch.is_some()
+            && git(&["status", "--porcelain"]).is_some_and(|s| !s.trim().is_empty());
+        self.remote_url = git(&["remote", "get-url", "origin"]).map(|url| url.trim().to_string());
+        self.provider = self
+            .remote_url
+            .as_deref()
+            .map(Provider::from_remote)
+            .unwrap_or_default();
+    }
+
+    /// Fetch PRs with one GraphQL request, logging it alongside CLI calls.
+    /// Returns false if the CLI should be tried instead.
+    fn refresh_prs_from_api(&mut self, api: &GitHubApi) -> bool {
+        if self.provider != Provider::GitHub {
+            return false;
+        }
+        let Some((owner, name)) = self.remote_url.as_deref().and_then(github::repo_slug) else {
+            return false;
+        };
+        self.gh_log.clear();
+        self.last_error = None;
+
+        let result = api.fetch_prs(&owner, &name, self.merged_pr_limit);
+        self.gh_log.push(CommandLog {
+            command: format!("POST api.github.com/graphql ({}/{})", owner, name),
+            status: match &result {
+                Ok(_) => "ok".to_string(),
+                Err(e) => format!("failed: {}", e),
+            },
+            stdout: String::new(),
+            stderr: String::new(),
+        });
+        let Ok(prs) = result else {
+            return false;
+        };
+        self.open_prs = prs.open;
+        (self.merged_prs, self.closed_prs) = (prs.merged, prs.closed);
+        self.closed_prs_loaded = true;
+        self.merged_total = prs.merged_total;
+        self.closed_total = prs.closed_total;
+        self.totals_counted_at = Some(self.newest_closed());
+        true
+    }
+
+    fn refresh_prs(&mut self, runner: &dyn CommandRunner) {
+        let Some(repo_path) = self.repo_path.clone() else {
+            return;
+        };
+        self.gh_log.clear();
+        self.last_error = None;
+
+        let api = self.provider.api();
+        let limit = self.merged_pr_limit;
+        let (open, closed) = {
+            let mut cli = |args: &[&str]| self.cli(runner, api.program(), &repo_path, args);
+            (api.open_prs(&mut cli), api.closed_prs(&mut cli, limit))
+        };
+
+        if let Some(prs) = open {
+            self.open_prs = prs;
+        }
+        let Some((merged, closed)) = closed else {
+            return;
+        };
+        (self.merged_prs, self.closed_prs) = (merged, closed);
+        self.closed_prs_loaded = true;
+
+        // Totals only move when something new is merged or closed
+        let newest = self.newest_closed();
+        if self.totals_counted_at == Some(newest) {
+            return;
+        }
+        let (merged_total, closed_total) = {
+            let mut cli = |args: &[&str]| self.cli(runner, api.program(), &repo_path, args);
+            api.totals(&mut cli)
+        };
+        self.merged_total = merged_total;
+        self.closed_total = closed_total;
+        if merged_total.is_some() {
+            self.totals_counted_at = Some(newest);
+        }
+    }
+
+    /// Numbers of the newest merged and closed PRs fetched
+    fn newest_closed(&self) -> (Option<u32>, Option<u32>) {
+        let newest = |prs: &[PullRequest]| prs.first().map(|pr| pr.number);
+        (newest(&self.merged_prs), newest(&self.closed_prs))
+    }
+
+    /// Run the provider's CLI in the repo, recording the invocation in
+    /// `gh_log`. Returns stdout if it succeeded.
+    fn cli(
+        &mut self,
+        runner: &dyn CommandRunner,
+        program: &str,
+        repo_path: &str,
+        args: &[&str],
+    ) -> Option<Vec<u8>> {
+        let output = runner.run(program, args, Some(Path::new(repo_path)));
+
+        let mut log = CommandLog {
+            command: format!("{} {}", program, args.join(" ")),
+            status: String::new(),
+            stdout: String::new(),
+            stderr: String::new(),
+        };
+        let stdout = match output {
+            Ok(output) => {
+                log.status = output.status.to_string();
+                log.stdout = String::from_utf8_lossy(&output.stdout).to_string();
+                log.stderr = String::from_utf8_lossy(&output.stderr).to_string();
+                if output.status.success() {
+                    Some(output.stdout)
+                } else {
+                    if is_auth_error(&log.stderr) {
+                        let detail = log.stderr.lines().next().unwrap_or_default().trim();
+                        self.last_error = Some(format!(
+                            "{} is not authenticated; run `{} auth login` ({})",
+                            program, program, detail
+                        ));
+                    }
+                    None
+                }
+            }
+            Err(e) => {
+                log.status = format!("failed to spawn: {}", e);
+                self.gh_missing = e.kind() == io::ErrorKind::NotFound;
+                None
+            }
+        };
+        self.gh_log.push(log);
+        stdout
+    }
+
+    /// Time since `created_at`
+    pub fn uptime(&self) -> Option<std::time::Duration> {
+        self.created_at?.elapsed().ok()
+    }
+
     pub fn running_agent_count(&self) -> usize {
         self.agents.iter().filter(|a| a.is_running).count()
     }
 
+    /// Running agents out of all agents, e.g. `2/6`
+    pub fn agent_summary(&self) -> String {
+        format!("{}/{}", self.running_agent_count(), self.agents.len())
+    }
+
+    /// Agent that opened `pr`, going by the branch naming `pattern` (see
+    /// `Config::pr_branch_pattern`). The longest matching prefix wins, so
+    /// `impl-alpha2/` isn't credited to `impl-alpha`.
+    pub fn pr_agent(&self, pr: &PullRequest, pattern: &str) -> Option<&Agent> {
+        self.agents
+            .iter()
+            .map(|agent| (agent, pattern.replace("{agent}", &agent.name)))
+            .filter(|(_, prefix)| pr.head_ref_name.starts_with(prefix.as_str()))
+            .max_by_key(|(_, prefix)| prefix.len())
+            .map(|(agent, _)| agent)
+    }
+
+    /// Open PRs whose branch `pr_agent` credits to `agent`
+    pub fn open_pr_count(&self, agent: &Agent, pattern: &str) -> usize {
+        self.open_prs
+            .iter()
+            .filter(|pr| {
+                self.pr_agent(pr, pattern)
+                    .is_some_and(|a| a.name == agent.name)
+            })
+            .count()
+    }
+
+    /// Key for per-repo persisted state, stable across instance restarts
+    pub fn state_key(&self) -> String {
+        self.repo_path.clone().unwrap_or_else(|| self.id.clone())
+    }
+
     pub fn repo_name(&self) -> String {
         self.repo_path
             .as_ref()
@@ -131,14 +415,52 @@ impl Instance {
     }
 }
 
-/// Discover all running amptown instances by scanning tmux sessions
-pub fn discover_instances() -> HashMap<String, Instance> {
+/// Recently merged PRs fetched per instance until `M` loads more
+pub const DEFAULT_MERGED_PR_LIMIT: u32 = 10;
+
+/// Whether gh's or glab's stderr looks like a missing or expired login
+fn is_auth_error(stderr: &str) -> bool {
+    let stderr = stderr.to_lowercase();
+    [
+        "auth login",
+        "not logged in",
+        "authentication",
+        "bad credentials",
+        "http 401",
+        "401 unauthorized",
+    ]
+    .iter()
+    .any(|needle| stderr.contains(needle))
+}
+
+/// Result of scanning tmux sessions and log directories
+pub struct Discovery {
+    pub instances: HashMap<String, Instance>,
+    /// All `amptown-*` tmux session names
+    pub sessions: Vec<String>,
+    /// Problems hit while discovering, e.g. log patterns that fail to compile
+    pub warnings: Vec<String>,
+    /// tmux couldn't be run at all, so only stopped instances can be found
+    pub tmux_missing: bool,
+}
+
+/// Discover all running amptown instances by scanning tmux sessions, then
+/// the built-in log roots and the user's `log_patterns` for stopped ones
+pub fn discover_instances(runner: &dyn CommandRunner, log_patterns: &[String]) -> Discovery {
+    discover_with(runner, &log_roots(), log_patterns)
+}
+
+fn discover_with(
+    runner: &dyn CommandRunner,
+    log_roots: &[PathBuf],
+    log_patterns: &[String],
+) -> Discovery {
     let mut instances: HashMap<String, Instance> = HashMap::new();
+    let mut amptown_sessions = Vec::new();
 
     // List all tmux sessions
-    let output = Command::new("tmux")
-        .args(["list-sessions", "-F", "#{session_name}"])
-        .output();
+    let output = runner.run("tmux", &["list-sessions", "-F", "#{session_name}"], None);
+    let tmux_missing = matches!(&output, Err(e) if e.kind() == io::ErrorKind::NotFound);
 
     if let Ok(output) = output {
         if output.status.success() {
@@ -146,14 +468,14 @@ pub fn discover_instances() -> HashMap<String, Instance> {
             for session in sessions.lines() {
                 // Match pattern: amptown-{instance_id}-{agent_name}
                 if let Some(rest) = session.strip_prefix("amptown-") {
-                    // Extract instance ID (8 hex chars)
-                    if rest.len() > 9 && rest.chars().nth(8) == Some('-') {
-                        let instance_id = &rest[..8];
-                        if instance_id.chars().all(|c| c.is_ascii_hexdigit()) {
-                            instances
-                                .entry(instance_id.to_string())
-                                .or_insert_with(|| Instance::new(instance_id.to_string()));
-                        }
+                    amptown_sessions.push(session.to_string());
+                    // The agent name follows the id, so require something after it
+                    if let Some(instance_id) =
+                        parse_instance_id(rest).filter(|id| rest.len() > id.len() + 1)
+                    {
+                        instances
+                            .entry(instance_id.to_string())
+                            .or_insert_with(|| Instance::new(instance_id.to_string()));
                     }
                 }
             }
@@ -161,41 +483,540 @@ pub fn discover_instances() -> HashMap<String, Instance> {
     }
 
     // Also check for log directories to find instances that might have stopped
-    discover_from_logs(&mut instances);
+    let warnings = discover_from_logs(&mut instances, log_roots, log_patterns);
 
-    instances
+    Discovery {
+        instances,
+        sessions: amptown_sessions,
+        warnings,
+        tmux_missing,
+    }
 }
 
-fn discover_from_logs(instances: &mut HashMap<String, Instance>) {
-    let mut patterns: Vec<String> = vec!["/tmp/amptown-*/logs".to_string()];
-
-    if let Ok(tmpdir) = std::env::var("TMPDIR") {
-        let tmpdir = tmpdir.trim_end_matches('/');
-        patterns.insert(0, format!("{}/amptown-*/logs", tmpdir));
-    }
-
-    patterns.push("/var/folders/*/*/*/*/amptown-*/logs".to_string());
-
-    for pattern in &patterns {
-        if let Ok(paths) = glob::glob(pattern) {
-            for path in paths.flatten() {
-                if path.is_dir() {
-                    // Extract instance ID from path like /tmp/amptown-abc12345/logs
-                    if let Some(parent) = path.parent() {
-                        if let Some(dir_name) = parent.file_name() {
-                            let dir_str = dir_name.to_string_lossy();
-                            if let Some(id) = dir_str.strip_prefix("amptown-") {
-                                if id.len() >= 6 {
-                                    let instance = instances
-                                        .entry(id.to_string())
-                                        .or_insert_with(|| Instance::new(id.to_string()));
-                                    instance.logs_dir = Some(path.to_string_lossy().to_string());
-                                }
-                            }
-                        }
-                    }
-                }
+/// Instance id at the start of what follows `amptown-` in a session name
+/// (`{id}-{agent}`) or log directory (`{id}/logs`). Session ids are 8 hex
+/// chars but log directories come from `mktemp`, so any run of at least 6
+/// ASCII alphanumerics is accepted, the same way for both.
+fn parse_instance_id(rest: &str) -> Option<&str> {
+    let id = rest.split(['-', '/']).next()?;
+    (id.len() >= 6 && id.chars().all(|c| c.is_ascii_alphanumeric())).then_some(id)
+}
+
+/// Merge instances whose sessions resolved to the same `repo_path`, e.g. a
+/// restarted amptown whose old log directory is still around. The one with
+/// more running agents is kept; the other contributes agents that are
+/// running only there and a log directory if the kept one has none.
+pub fn merge_duplicates(instances: &mut Vec<Instance>) {
+    let mut merged: Vec<Instance> = Vec::with_capacity(instances.len());
+    for instance in instances.drain(..) {
+        let existing = instance.repo_path.as_ref().and_then(|path| {
+            merged
+                .iter_mut()
+                .find(|m| m.repo_path.as_ref() == Some(path))
+        });
+        let Some(existing) = existing else {
+            merged.push(instance);
+            continue;
+        };
+        let (mut keep, other) = if instance.running_agent_count() > existing.running_agent_count() {
+            (instance, existing.clone())
+        } else {
+            (existing.clone(), instance)
+        };
+        for agent in other.agents {
+            match keep.agents.iter_mut().find(|a| a.name == agent.name) {
+                Some(slot) if agent.is_running && !slot.is_running => *slot = agent,
+                Some(_) => {}
+                None => keep.agents.push(agent),
             }
         }
+        if keep.logs_dir.is_none() {
+            keep.logs_dir = other.logs_dir;
+            keep.created_at = other.created_at;
+        }
+        *existing = keep;
+    }
+    *instances = merged;
+}
+
+/// Sessions that belong to no instance, or to one with neither a log
+/// directory nor a resolvable repo path. Call after refreshing `instances`.
+pub fn orphaned_sessions(sessions: &[String], instances: &[Instance]) -> Vec<String> {
+    sessions
+        .iter()
+        .filter(|session| {
+            let owner = instances
+                .iter()
+                .find(|i| session.starts_with(&format!("amptown-{}-", i.id)));
+            match owner {
+                Some(instance) => instance.logs_dir.is_none() && instance.repo_path.is_none(),
+                None => true,
+            }
+        })
+        .cloned()
+        .collect()
+}
+
+pub fn kill_session(runner: &dyn CommandRunner, session: &str) -> bool {
+    runner
+        .run("tmux", &["kill-session", "-t", session], None)
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// How many directory levels below each log root to look for `amptown-*`.
+/// macOS nests its per-user TMPDIR at varying depths, so a fixed glob
+/// misses some setups.
+pub const LOG_SEARCH_DEPTH: usize = 3;
+
+/// Existing temp directories amptown's `mktemp` may have used, canonicalized
+/// and deduplicated
+pub fn log_roots() -> Vec<PathBuf> {
+    let mut candidates: Vec<PathBuf> = Vec::new();
+    if let Some(tmpdir) = std::env::var_os("TMPDIR") {
+        candidates.push(tmpdir.into());
+    }
+    candidates.push(std::env::temp_dir());
+    candidates.push("/tmp".into());
+    if let Some(runtime_dir) = std::env::var_os("XDG_RUNTIME_DIR") {
+        candidates.push(runtime_dir.into());
+    }
+
+    let mut roots = Vec::new();
+    for root in candidates.iter().filter_map(|c| fs::canonicalize(c).ok()) {
+        if !roots.contains(&root) {
+            roots.push(root);
+        }
+    }
+    roots
+}
+
+/// Collect `amptown-*/logs` directories at most `depth` levels below `dir`.
+/// Symlinks aren't followed and amptown directories aren't descended into,
+/// which keeps the walk cheap enough to run on every refresh.
+fn find_log_dirs(dir: &Path, depth: usize, found: &mut Vec<PathBuf>) {
+    if depth == 0 {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if !entry.file_type().is_ok_and(|t| t.is_dir()) {
+            continue;
+        }
+        let path = entry.path();
+        if entry.file_name().to_string_lossy().starts_with("amptown-") {
+            let logs = path.join("logs");
+            if logs.is_dir() {
+                found.push(logs);
+            }
+        } else {
+            find_log_dirs(&path, depth - 1, found);
+        }
+    }
+}
+
+/// Search `roots` and glob `patterns` for log directories, returning an
+/// error message for each pattern that fails to compile. Bad patterns are
+/// skipped, not fatal.
+fn discover_from_logs(
+    instances: &mut HashMap<String, Instance>,
+    roots: &[PathBuf],
+    patterns: &[String],
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let mut log_dirs = Vec::new();
+    for root in roots {
+        find_log_dirs(root, LOG_SEARCH_DEPTH, &mut log_dirs);
+    }
+
+    for pattern in patterns {
+        let paths = match glob::glob(pattern) {
+            Ok(paths) => paths,
+            Err(e) => {
+                warnings.push(format!("invalid log pattern '{}': {}", pattern, e));
+                continue;
+            }
+        };
+        log_dirs.extend(paths.flatten().filter(|path| path.is_dir()));
+    }
+
+    for path in log_dirs {
+        // Extract instance ID from path like /tmp/amptown-abc12345/logs
+        let Some(dir_name) = path.parent().and_then(|p| p.file_name()) else {
+            continue;
+        };
+        let dir_str = dir_name.to_string_lossy();
+        if let Some(id) = dir_str.strip_prefix("amptown-").and_then(parse_instance_id) {
+            let instance = instances
+                .entry(id.to_string())
+                .or_insert_with(|| Instance::new(id.to_string()));
+            instance.logs_dir = Some(path.to_string_lossy().to_string());
+            // Birth time where the filesystem records it; the directory's
+            // mtime only moves when agent logs are added
+            instance.created_at = fs::metadata(&path)
+                .and_then(|m| m.created().or_else(|_| m.modified()))
+                .ok();
+        }
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::pr::PR_JSON_FIELDS;
+    use crate::provider::PR_TOTALS_QUERY;
+    use crate::refresh::{fetch_instances, RefreshOptions};
+    use crate::runner::mock::MockRunner;
+
+    const GH_OPEN: &str = r#"[{"number": 12, "title": "Add parser", "state": "OPEN",
+        "author": {"login": "impl-bot"}, "createdAt": "2024-05-01T12:00:00Z",
+        "headRefName": "impl-alpha/parser", "headRefOid": "abc", "mergeable": "CONFLICTING",
+        "url": "https://github.com/o/r/pull/12", "statusCheckRollup": [], "reviewDecision": null,
+        "additions": 120, "deletions": null, "comments": [{"body": "LGTM?"}, {"body": "Nope"}]}]"#;
+    const GH_MERGED: &str = r#"[
+        {"number": 10, "title": "Old fix", "state": "MERGED", "author": {"login": "impl-bot"},
+         "createdAt": "2024-04-01T12:00:00Z", "headRefName": "fix"}]"#;
+    const GH_CLOSED: &str = r#"[
+        {"number": 11, "title": "Abandoned", "state": "CLOSED", "author": {"login": "impl-bot"},
+         "createdAt": "2024-04-02T12:00:00Z", "headRefName": "wip"}]"#;
+
+    #[test]
+    fn discovers_instances_from_tmux_sessions() {
+        let runner = MockRunner::default().reply(
+            "tmux list-sessions -F #{session_name}",
+            "amptown-deadbeef-impl-alpha\namptown-deadbeef-reviewer-beta\nscratch\namptown-abc-impl-alpha\n",
+        );
+        let discovery = discover_instances(&runner, &[]);
+
+        assert!(discovery.instances.contains_key("deadbeef"));
+        assert!(!discovery.instances.contains_key("abc"));
+        assert_eq!(discovery.sessions.len(), 3);
+    }
+
+    #[test]
+    fn sessions_and_log_dirs_merge_into_one_instance() {
+        let root = std::env::temp_dir().join(format!("ampwatch-merge-test-{}", std::process::id()));
+        for id in ["deadbeef12", "abc123"] {
+            std::fs::create_dir_all(root.join(format!("amptown-{}", id)).join("logs")).unwrap();
+        }
+        let runner = MockRunner::default().reply(
+            "tmux list-sessions -F #{session_name}",
+            "amptown-deadbeef12-impl-alpha\namptown-deadbeef12-reviewer-beta\namptown-abc123-impl-beta\n",
+        );
+        let patterns = vec![format!("{}/amptown-*/logs", root.display())];
+        let discovery = discover_with(&runner, &[], &patterns);
+
+        let mut ids: Vec<&String> = discovery.instances.keys().collect();
+        ids.sort();
+        assert_eq!(ids, ["abc123", "deadbeef12"]);
+        assert!(discovery.instances.values().all(|i| i.logs_dir.is_some()));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn parses_instance_ids() {
+        assert_eq!(parse_instance_id("deadbeef-impl-alpha"), Some("deadbeef"));
+        assert_eq!(parse_instance_id("aB3xYz"), Some("aB3xYz"));
+        assert_eq!(parse_instance_id("aB3xYz/logs"), Some("aB3xYz"));
+        assert_eq!(parse_instance_id("abc-impl-alpha"), None);
+        assert_eq!(parse_instance_id("dead_beef-impl"), None);
+    }
+
+    #[test]
+    fn agent_summary_counts_actual_agents() {
+        let mut instance = Instance::new("deadbeef".to_string());
+        instance.agents.truncate(4);
+        instance.agents[0].is_running = true;
+        instance.agents[2].is_running = true;
+
+        assert_eq!(instance.running_agent_count(), 2);
+        assert_eq!(instance.agent_summary(), "2/4");
+
+        instance.agents.clear();
+        assert_eq!(instance.agent_summary(), "0/0");
+    }
+
+    #[test]
+    fn merges_instances_sharing_a_repo() {
+        let mut stale = Instance::new("abc123".to_string());
+        stale.repo_path = Some("/src/widgets".to_string());
+        stale.logs_dir = Some("/tmp/amptown-abc123/logs".to_string());
+        stale.agents[0].is_running = true;
+
+        let mut live = Instance::new("deadbeef".to_string());
+        live.repo_path = Some("/src/widgets".to_string());
+        live.agents[3].is_running = true;
+        live.agents[4].is_running = true;
+
+        let other = Instance::new("cafe01".to_string());
+        let mut instances = vec![stale, other, live];
+        merge_duplicates(&mut instances);
+
+        assert_eq!(instances.len(), 2);
+        let merged = &instances[0];
+        assert_eq!(merged.id, "deadbeef");
+        assert_eq!(merged.logs_dir.as_deref(), Some("/tmp/amptown-abc123/logs"));
+        assert_eq!(merged.running_agent_count(), 3);
+        assert_eq!(merged.agents.len(), 6);
+        assert_eq!(
+            merged.agents[0].session_name(),
+            "amptown-abc123-reviewer-alpha"
+        );
+    }
+
+    #[test]
+    fn log_state_follows_agents_across_a_merge() {
+        let root = std::env::temp_dir().join(format!("ampwatch-carry-test-{}", std::process::id()));
+        let marker = |n: u32| {
+            format!(
+                "[2024-05-01 12:00:00] Starting impl-alpha iteration {}\n",
+                n
+            )
+        };
+        let log_path = |id: &str| root.join(format!("amptown-{}/logs/impl-alpha.log", id));
+        for id in ["aaa111", "bbb222"] {
+            std::fs::create_dir_all(log_path(id).parent().unwrap()).unwrap();
+        }
+        // Long lines in the kept instance's log, so a scan resumed at the
+        // other log's offset would land mid-file and miscount
+        let filler = format!("{}\n", "x".repeat(200));
+        std::fs::write(
+            log_path("aaa111"),
+            (1..=3).map(|n| marker(n) + &filler).collect::<String>(),
+        )
+        .unwrap();
+        std::fs::write(log_path("bbb222"), marker(1) + &marker(2)).unwrap();
+
+        let mut runner = MockRunner::default();
+        for session in [
+            "amptown-aaa111-reviewer-alpha",
+            "amptown-aaa111-reviewer-beta",
+            "amptown-bbb222-impl-alpha",
+        ] {
+            runner = runner
+                .reply(&format!("tmux has-session -t {}", session), "")
+                .reply(
+                    &format!(
+                        "tmux display-message -t {} -p #{{pane_current_path}}",
+                        session
+                    ),
+                    "/src/widgets\n",
+                );
+        }
+        // One refresh as `fetch_instances` does it, before and after merging
+        let refresh = |previous: &[Instance]| -> Vec<Instance> {
+            ["aaa111", "bbb222"]
+                .iter()
+                .map(|id| {
+                    let mut instance = Instance::new(id.to_string());
+                    instance.logs_dir = Some(
+                        root.join(format!("amptown-{}/logs", id))
+                            .display()
+                            .to_string(),
+                    );
+                    instance.carry_over(previous);
+                    instance.refresh(&runner, None, &LogScan::default());
+                    instance
+                })
+                .collect()
+        };
+        let iterations = |instance: &Instance| instance.agents[3].iterations;
+
+        let mut merged = refresh(&[]);
+        merge_duplicates(&mut merged);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(
+            merged[0].agents[3].session_name(),
+            "amptown-bbb222-impl-alpha"
+        );
+        assert_eq!(iterations(&merged[0]), 2);
+
+        let mut log = std::fs::OpenOptions::new()
+            .append(true)
+            .open(log_path("bbb222"))
+            .unwrap();
+        std::io::Write::write_all(&mut log, marker(3).as_bytes()).unwrap();
+        let second = refresh(&merged);
+        assert_eq!(iterations(&second[0]), 3);
+        assert_eq!(iterations(&second[1]), 3);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn links_prs_to_agents_by_branch() {
+        let mut instance = Instance::new("deadbeef".to_string());
+        instance.open_prs = serde_json::from_str(GH_OPEN).unwrap();
+        let pr = instance.open_prs[0].clone();
+
+        let agent = instance.pr_agent(&pr, "{agent}/").map(|a| a.name.as_str());
+        assert_eq!(agent, Some("impl-alpha"));
+        assert_eq!(instance.open_pr_count(&instance.agents[3], "{agent}/"), 1);
+        assert_eq!(instance.open_pr_count(&instance.agents[4], "{agent}/"), 0);
+        assert!(instance.pr_agent(&pr, "amp/{agent}-").is_none());
+    }
+
+    #[test]
+    fn refresh_parses_agents_and_prs() {
+        let runner = MockRunner::default()
+            .reply("tmux has-session -t amptown-deadbeef-impl-alpha", "")
+            .reply(
+                "tmux display-message -t amptown-deadbeef-impl-alpha -p #{pane_current_path}",
+                "/src/widgets\n",
+            )
+            .reply(&format!("gh pr list --json {}", PR_JSON_FIELDS), GH_OPEN)
+            .reply(
+                &format!(
+                    "gh pr list --state merged --limit 10 --json {}",
+                    PR_JSON_FIELDS
+                ),
+                GH_MERGED,
+            )
+            .reply(
+                &format!(
+                    "gh pr list --state closed --search is:unmerged --limit 10 --json {}",
+                    PR_JSON_FIELDS
+                ),
+                GH_CLOSED,
+            )
+            .reply(
+                &format!(
+                    "gh api graphql -F owner={{owner}} -F name={{repo}} -f {}",
+                    PR_TOTALS_QUERY
+                ),
+                r#"{"data": {"repository": {"merged": {"totalCount": 57}, "closed": {"totalCount": 3}}}}"#,
+            )
+            .reply("git rev-parse --abbrev-ref HEAD", "impl-alpha/parser\n")
+            .reply("git status --porcelain", " M src/parser.rs\n");
+        let mut instance = Instance::new("deadbeef".to_string());
+        instance.refresh(&runner, None, &LogScan::default());
+
+        assert_eq!(instance.repo_path.as_deref(), Some("/src/widgets"));
+        assert_eq!(instance.repo_name(), "widgets");
+        assert_eq!(instance.running_agent_count(), 1);
+        assert_eq!(instance.branch.as_deref(), Some("impl-alpha/parser"));
+        assert!(instance.dirty);
+
+        assert_eq!(instance.open_prs.len(), 1);
+        let pr = &instance.open_prs[0];
+        assert_eq!(pr.number, 12);
+        assert_eq!(pr.author.login, "impl-bot");
+        assert!(pr.is_conflicting());
+        assert_eq!(pr.review_decision, "");
+        assert_eq!((pr.additions, pr.deletions, pr.changed_files), (120, 0, 0));
+        assert_eq!(pr.comments, 2);
+
+        assert_eq!(
+            instance
+                .merged_prs
+                .iter()
+                .map(|p| p.number)
+                .collect::<Vec<_>>(),
+            [10]
+        );
+        assert_eq!(
+            instance
+                .closed_prs
+                .iter()
+                .map(|p| p.number)
+                .collect::<Vec<_>>(),
+            [11]
+        );
+        assert_eq!(
+            (instance.merged_total, instance.closed_total),
+            (Some(57), Some(3))
+        );
+        assert_eq!(instance.gh_log.len(), 4);
+
+        // Nothing new merged or closed, so the totals aren't counted again
+        instance.refresh(&runner, None, &LogScan::default());
+        assert_eq!(instance.gh_log.len(), 3);
+        assert_eq!(instance.merged_total, Some(57));
+    }
+
+    #[test]
+    fn failed_gh_call_keeps_previous_prs() {
+        let runner = MockRunner::default()
+            .reply(
+                "tmux list-sessions -F #{session_name}",
+                "amptown-deadbeef-reviewer-alpha\n",
+            )
+            .reply(
+                "tmux display-message -t amptown-deadbeef-reviewer-alpha -p #{pane_current_path}",
+                "/src/widgets\n",
+            );
+        let mut previous = Instance::new("deadbeef".to_string());
+        previous.open_prs = serde_json::from_str(GH_OPEN).unwrap();
+        previous.closed_prs_loaded = true;
+
+        let options = RefreshOptions::new(&Config::default());
+        let refresh = fetch_instances(&runner, &options, vec![previous], HashMap::new(), &|_| {});
+        let instance = refresh
+            .instances
+            .iter()
+            .find(|i| i.id == "deadbeef")
+            .unwrap();
+
+        assert_eq!(instance.open_prs.len(), 1);
+        assert!(instance.closed_prs_loaded);
+        assert_eq!(instance.gh_log[0].status, "exit status: 1");
+    }
+
+    #[test]
+    fn invalid_log_pattern_does_not_stop_discovery() {
+        let root = std::env::temp_dir().join(format!("ampwatch-test-{}", std::process::id()));
+        let logs = root.join("amptown-abc123").join("logs");
+        std::fs::create_dir_all(&logs).unwrap();
+
+        let patterns = vec![
+            "[invalid".to_string(),
+            format!("{}/amptown-*/logs", root.display()),
+        ];
+        let mut instances = HashMap::new();
+        let warnings = discover_from_logs(&mut instances, &[], &patterns);
+
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("[invalid"));
+        assert_eq!(
+            instances["abc123"].logs_dir.as_deref(),
+            Some(logs.to_string_lossy().as_ref())
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn finds_log_dirs_at_varying_depths() {
+        let root = std::env::temp_dir().join(format!("ampwatch-walk-test-{}", std::process::id()));
+        let planted = [
+            ("", "abc123"),
+            ("zz/T", "def456"),
+            ("a/b", "ghi789"),
+            ("a/b/c", "toodeep"),
+        ];
+        for (parent, id) in planted {
+            std::fs::create_dir_all(
+                root.join(parent)
+                    .join(format!("amptown-{}", id))
+                    .join("logs"),
+            )
+            .unwrap();
+        }
+
+        let mut instances = HashMap::new();
+        let warnings = discover_from_logs(&mut instances, std::slice::from_ref(&root), &[]);
+
+        assert!(warnings.is_empty());
+        let mut ids: Vec<&String> = instances.keys().collect();
+        ids.sort();
+        assert_eq!(ids, ["abc123", "def456", "ghi789"]);
+
+        std::fs::remove_dir_all(&root).unwrap();
     }
 }
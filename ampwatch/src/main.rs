@@ -1,254 +1,2320 @@
 use anyhow::Result;
+use chrono::{TimeDelta, Utc};
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, MouseButton,
+        MouseEvent, MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    layout::{Alignment, Constraint, Direction, Layout, Position, Rect},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Tabs, Wrap},
     Frame, Terminal,
 };
 use std::{
-    io,
-    process::Command,
-    sync::{Arc, Mutex},
+    collections::{BTreeSet, HashMap, VecDeque},
+    fs::File,
+    io::{self, BufRead, BufReader, Read, Write},
+    ops::Bound::{Excluded, Unbounded},
+    process::{Child, Command, ExitStatus, Stdio},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc, Arc, Mutex, MutexGuard,
+    },
     thread,
     time::{Duration, Instant},
 };
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 mod agent;
+mod cli;
+mod clipboard;
+mod config;
+mod doctor;
+mod events;
+mod github;
 mod instance;
+mod notify;
 mod pr;
+mod provider;
+mod refresh;
+mod runner;
+mod state;
+mod status;
+mod theme;
+mod web;
 
-use agent::AgentType;
-use instance::{discover_instances, Instance};
-use pr::PullRequest;
+use agent::{Agent, AgentStatus, AgentType};
+use clap::Parser;
+use cli::Cli;
+use clipboard::Clipboard;
+use config::{Config, ThemePreset};
+use instance::Instance;
+use pr::{CheckStatus, PullRequest, SortMode};
+use refresh::{fetch_instances, Refresh, RefreshOptions};
+use runner::SystemRunner;
+use state::{State, UiState};
+use theme::Theme;
+
+/// Filters narrowing which instances are shown in the selector
+#[derive(Default)]
+struct InstanceFilter {
+    only_open_prs: bool,
+}
+
+impl InstanceFilter {
+    fn matches(&self, instance: &Instance) -> bool {
+        !self.only_open_prs || !instance.open_prs.is_empty()
+    }
+}
+
+/// Something drawn last frame that responds to a mouse click
+enum ClickTarget {
+    Tab(usize),
+    /// Index into the visible instances
+    Instance(usize),
+    /// Rows of the overview, scrolled down by `offset`
+    OverviewList {
+        offset: usize,
+    },
+    /// Rows of the PR list, scrolled down by `offset`. Each row maps to
+    /// an index into the displayed PRs, or `None` for an author header.
+    PrList {
+        rows: Vec<Option<usize>>,
+        offset: usize,
+    },
+    /// Rows of an agent column (two lines per agent)
+    AgentList {
+        names: Vec<String>,
+        offset: usize,
+    },
+}
+
+/// An action shown in the modal, waiting for the user to confirm
+enum PendingAction {
+    Checkout {
+        number: u32,
+        branch: String,
+    },
+    /// `approved` is false when the PR lacks an approving review, which
+    /// takes `Y` rather than `y` to override
+    Merge {
+        number: u32,
+        approved: bool,
+    },
+    /// Quit while agents are still running; a second `q` also confirms
+    Quit,
+}
+
+impl PendingAction {
+    fn confirm_key(&self) -> char {
+        match self {
+            PendingAction::Merge {
+                approved: false, ..
+            } => 'Y',
+            _ => 'y',
+        }
+    }
+}
+
+/// One-line prompt shown over the main view; `action` runs on Enter
+struct TextInput {
+    prompt: String,
+    value: String,
+    action: InputAction,
+}
+
+enum InputAction {
+    /// `gh pr review --approve`, with the input as an optional comment
+    Approve { number: u32 },
+    /// Type the input into a running agent's pane
+    SendKeys { agent: String },
+}
+
+/// Sent by the background refresh thread
+enum RefreshMessage {
+    /// This instance's data is in; it lands with the rest in `Done`
+    Refreshed(String),
+    Done(Refresh),
+}
+
+/// Agents, Open PRs, Merged PRs, Closed PRs
+const TAB_COUNT: usize = 4;
+
+/// Smallest terminal the layout renders in; below this `ui` shows a notice
+const MIN_WIDTH: u16 = 80;
+const MIN_HEIGHT: u16 = 20;
+
+/// Longest instance label in the selector before it's cut with `…`
+const MAX_INSTANCE_LABEL: usize = 24;
+
+/// How long a toast replaces the footer hints
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+
+/// Animated spinner frames
+const SPINNER_FRAMES: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+fn spinner_frame(tick: usize) -> &'static str {
+    SPINNER_FRAMES[tick % SPINNER_FRAMES.len()]
+}
+
+/// Completed summaries keyed by repo, PR number, and head commit
+type SummaryCache = Arc<Mutex<HashMap<(String, u32, String), String>>>;
+
+/// Review-order recommendations keyed by repo and the open PR numbers
+type RecommendationCache = Arc<Mutex<HashMap<(String, Vec<u32>), String>>>;
+
+/// Lines of an agent log shown by `L`
+const LOG_VIEW_LINES: usize = 2000;
+
+/// Lines of a running agent's tmux pane kept for the detail panel
+const PANE_PREVIEW_LINES: usize = 200;
+
+/// How often the selected agent's pane is captured again while it stays
+/// selected
+const PANE_PREVIEW_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A captured pane: the tmux session and its lines or the error
+type PaneCapture = (String, Result<Vec<String>, String>);
 
 struct App {
     instances: Vec<Instance>,
-    selected_instance: usize,
+    selected_instance: usize, // Index into the visible (filtered) instances
+    instance_filter: InstanceFilter,
+    hide_stopped_agents: bool,
+    focus_instance: Option<String>, // From --instance; selected once the first refresh lands
+    restore_ui: Option<UiState>,    // Saved selection, restored once the first refresh lands
+    // Session name and captured pane of the selected running agent
+    pane_preview: Option<PaneCapture>,
+    pending_pane_preview: Option<mpsc::Receiver<PaneCapture>>,
+    last_pane_capture: Instant,
+
+    // PR search (`/`)
+    searching: bool, // Typing into the query
+    search_query: String,
+    author_filter: Option<String>, // Only PRs by this login
+    hide_drafts: bool,
+    group_by_author: bool, // Open PRs under author headers (`A`)
+    sort_mode: SortMode,
+    warnings: Vec<String>,
+    env_warnings: Vec<String>, // Missing tools; shown above other warnings
 
     // UI state
-    selected_tab: usize,  // 0: Agents, 1: Open PRs, 2: Merged PRs
+    selected_tab: usize, // 0: Agents, 1: Open PRs, 2: Merged PRs, 3: Closed PRs
     pr_list_state: ListState,
     agent_list_state: ListState,
     instance_list_state: ListState,
+    show_overview: bool, // Cross-instance summary in place of the tabs (`0`)
+    overview_list_state: ListState,
 
     // Modal state
     show_modal: bool,
+    modal_title: String,
     modal_content: Arc<Mutex<String>>,
     modal_loading: Arc<Mutex<bool>>,
+    modal_process: Arc<Mutex<Option<Child>>>, // amp process feeding the modal; taken when cancelled
+    modal_generation: Arc<AtomicUsize>,       // Bumped on open and close; see `ModalJob`
+    summary_cache: SummaryCache,
+    recommendation_cache: RecommendationCache,
+    modal_scroll: u16,
+    modal_scroll_to_end: bool, // Jump to the bottom once loading finishes
+    modal_footer: Option<String>, // Shown on the modal's bottom border
+    summary_pr: Option<PullRequest>, // PR shown in the modal, if it holds a summary
+    pending_action: Option<PendingAction>, // gh command awaiting confirmation
+    click_targets: Vec<(Rect, ClickTarget)>, // Clickable regions of the last frame
+    refresh_requested: Arc<AtomicBool>, // Set by background commands that change PRs
+    merged_pr_limits: HashMap<String, u32>, // Instance id to merged PRs fetched, raised by `M`
+
+    text_input: Option<TextInput>, // Prompt shown over everything else
+
+    // Review checklist overlay
+    show_checklist: bool,
+    checklist_cursor: usize,
+
+    // Key binding overlay
+    show_help: bool,
+    help_scroll: u16,
+
+    // Transient footer message
+    toast: Option<(String, Instant)>,
+    clipboard: Clipboard,
+
+    // Persisted state
+    state: State,
+
+    theme: Theme,
+    theme_preset: ThemePreset, // Toggled with `t`; starts as the config's
+    config: Config,
+    runner: SystemRunner,            // Runs tmux and gh as configured
+    refresh_options: RefreshOptions, // Shares one GitHub API client across refreshes
+
+    // Alerts
+    last_alert: Option<Instant>,
+
+    // Shared with the --web dashboard, if enabled
+    web_snapshot: Option<web::Snapshot>,
+
+    // Refresh
+    last_refresh: Instant,
+    last_success: Option<Instant>, // When a refresh last landed, for the header
+    pending_refresh: Option<mpsc::Receiver<RefreshMessage>>,
+    paused: bool,  // Skip auto-refresh; `r` still refreshes
+    loading: bool, // True until the first refresh lands
+
+    // Live indicator
+    tick: usize,
+}
+
+impl App {
+    fn new(config: Config) -> Self {
+        let mut app = Self {
+            instances: Vec::new(),
+            selected_instance: 0,
+            instance_filter: InstanceFilter::default(),
+            hide_stopped_agents: false,
+            focus_instance: None,
+            restore_ui: None,
+            pane_preview: None,
+            pending_pane_preview: None,
+            last_pane_capture: Instant::now(),
+            searching: false,
+            search_query: String::new(),
+            author_filter: None,
+            hide_drafts: false,
+            group_by_author: false,
+            sort_mode: SortMode::default(),
+            warnings: Vec::new(),
+            env_warnings: Vec::new(),
+            selected_tab: 0,
+            pr_list_state: ListState::default(),
+            agent_list_state: ListState::default(),
+            instance_list_state: ListState::default(),
+            show_overview: false,
+            overview_list_state: ListState::default().with_selected(Some(0)),
+            show_modal: false,
+            modal_title: String::new(),
+            modal_content: Arc::new(Mutex::new(String::new())),
+            modal_loading: Arc::new(Mutex::new(false)),
+            modal_process: Arc::default(),
+            modal_generation: Arc::default(),
+            summary_cache: SummaryCache::default(),
+            recommendation_cache: RecommendationCache::default(),
+            modal_scroll: 0,
+            modal_scroll_to_end: false,
+            modal_footer: None,
+            summary_pr: None,
+            pending_action: None,
+            click_targets: Vec::new(),
+            refresh_requested: Arc::default(),
+            merged_pr_limits: HashMap::new(),
+            text_input: None,
+            show_checklist: false,
+            show_help: false,
+            help_scroll: 0,
+            checklist_cursor: 0,
+            toast: None,
+            clipboard: Clipboard::default(),
+            state: State::load(),
+            theme: Theme::load(config.theme.preset, &config.theme),
+            theme_preset: config.theme.preset,
+            runner: SystemRunner::new(&config),
+            refresh_options: RefreshOptions::new(&config),
+            config,
+            last_alert: None,
+            web_snapshot: None,
+            last_refresh: Instant::now(),
+            last_success: None,
+            pending_refresh: None,
+            paused: false,
+            loading: true,
+            tick: 0,
+        };
+        app.instance_list_state.select(Some(0));
+        app.agent_list_state.select(Some(0));
+
+        let ui = app.state.ui.clone();
+        app.selected_tab = ui.tab.min(TAB_COUNT - 1);
+        app.instance_filter.only_open_prs = ui.only_open_prs;
+        app.hide_stopped_agents = ui.hide_stopped_agents;
+        app.hide_drafts = ui.hide_drafts;
+        app.sort_mode = ui.sort_mode;
+        if let Some(preset) = ui.theme {
+            app.set_theme(preset);
+        }
+        app.search_query = ui.search_query.clone();
+        app.restore_ui = Some(ui);
+        app
+    }
+
+    /// Selection and filters to save at exit
+    fn ui_state(&self) -> UiState {
+        UiState {
+            instance: self.current_instance().map(|i| i.state_key()),
+            tab: self.selected_tab,
+            pr: self.pr_list_state.selected().unwrap_or(0),
+            only_open_prs: self.instance_filter.only_open_prs,
+            hide_stopped_agents: self.hide_stopped_agents,
+            hide_drafts: self.hide_drafts,
+            sort_mode: self.sort_mode,
+            theme: (self.theme_preset != self.config.theme.preset).then_some(self.theme_preset),
+            author_filter: self.author_filter.clone(),
+            search_query: self.search_query.clone(),
+        }
+    }
+
+    /// Start a refresh on a background thread; `poll_refresh` applies it.
+    /// Does nothing if one is already in flight.
+    fn refresh(&mut self) {
+        if self.refreshing() {
+            return;
+        }
+        let (tx, rx) = mpsc::channel();
+        let previous = self.instances.clone();
+        let limits = self.merged_pr_limits.clone();
+        let runner = self.runner.clone();
+        let options = self.refresh_options.clone();
+        thread::spawn(move || {
+            let progress = tx.clone();
+            let refresh = fetch_instances(&runner, &options, previous, limits, &|i| {
+                let _ = progress.send(RefreshMessage::Refreshed(i.id.clone()));
+            });
+            let _ = tx.send(RefreshMessage::Done(refresh));
+        });
+        self.pending_refresh = Some(rx);
+        for instance in &mut self.instances {
+            instance.refreshing = true;
+        }
+    }
+
+    fn refreshing(&self) -> bool {
+        self.pending_refresh.is_some()
+    }
+
+    /// Apply a finished background refresh, if any
+    fn poll_refresh(&mut self) {
+        // Requests made mid-refresh wait for it, so they see the current state
+        if !self.refreshing() && self.refresh_requested.swap(false, Ordering::Relaxed) {
+            self.refresh();
+        }
+        while let Some(rx) = &self.pending_refresh {
+            match rx.try_recv() {
+                Ok(RefreshMessage::Refreshed(id)) => {
+                    if let Some(instance) = self.instances.iter_mut().find(|i| i.id == id) {
+                        instance.refreshing = false;
+                    }
+                }
+                Ok(RefreshMessage::Done(refresh)) => {
+                    self.pending_refresh = None;
+                    self.loading = false;
+                    self.apply_refresh(refresh);
+                }
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    self.pending_refresh = None;
+                    self.loading = false;
+                    self.last_refresh = Instant::now();
+                    for instance in &mut self.instances {
+                        instance.refreshing = false;
+                    }
+                }
+            }
+        }
+    }
+
+    fn apply_refresh(&mut self, refresh: Refresh) {
+        let Refresh {
+            instances,
+            warnings,
+            env_warnings,
+        } = refresh;
+        let new_env_warning = env_warnings.iter().find(|w| !self.env_warnings.contains(w));
+        let new_warning = warnings.iter().find(|w| !self.warnings.contains(w));
+        if let Some(new) = new_env_warning.or(new_warning) {
+            self.show_toast(format!("⚠ {}", new));
+        }
+        self.warnings = warnings;
+        self.env_warnings = env_warnings;
+
+        let events = events::diff(&self.instances, &instances);
+        if let Some(event) = events.iter().find(|e| e.is_alert()) {
+            self.show_toast(format!("⚠ {}", event.describe()));
+        }
+        self.alert(&events);
+        self.notify(&events);
+
+        // Remember what the cursors point at, not where, since ordering
+        // and lengths can change between refreshes
+        let instance_id = self.current_instance().map(|i| i.id.clone());
+        let pr_number = self.selected_pr().map(|pr| pr.number);
+        let agent_name = self.selected_agent().map(|a| a.name.clone());
+
+        self.instances = instances;
+        self.reselect(instance_id, pr_number, agent_name);
+
+        // Quietly skip whatever no longer exists; --instance below wins
+        if let Some(ui) = self.restore_ui.take() {
+            let key = ui.instance.as_deref();
+            let found = self
+                .visible_instances()
+                .iter()
+                .position(|&i| Some(self.instances[i].state_key().as_str()) == key);
+            if let Some(index) = found {
+                self.select_instance(index);
+            }
+            let has_author = |login: &String| {
+                self.current_instance().is_some_and(|i| {
+                    i.open_prs
+                        .iter()
+                        .chain(&i.merged_prs)
+                        .chain(&i.closed_prs)
+                        .any(|pr| &pr.author.login == login)
+                })
+            };
+            self.author_filter = ui.author_filter.filter(has_author);
+            if ui.pr < self.displayed_prs().len() {
+                self.pr_list_state.select(Some(ui.pr));
+            }
+        }
+
+        if let Some(focus) = self.focus_instance.take() {
+            let found = self.visible_instances().iter().position(|&i| {
+                let instance = &self.instances[i];
+                instance.id == focus || instance.repo_name() == focus
+            });
+            match found {
+                Some(index) => self.select_instance(index),
+                None => self.show_toast(format!("No instance matching '{}'", focus)),
+            }
+        }
+
+        if let Some(snapshot) = &self.web_snapshot {
+            *snapshot.lock().unwrap() = Some(self.instances.clone());
+        }
+
+        self.last_refresh = Instant::now();
+        self.last_success = Some(self.last_refresh);
+    }
+
+    /// Ring the bell (or run `alert_command`) for high-priority events,
+    /// at most once per `alert_debounce_secs`
+    fn alert(&mut self, events: &[events::Event]) {
+        let events: Vec<&events::Event> = events.iter().filter(|e| e.is_alert()).collect();
+        if !self.config.alert_bell || events.is_empty() {
+            return;
+        }
+        let debounce = Duration::from_secs(self.config.alert_debounce_secs);
+        if self.last_alert.is_some_and(|t| t.elapsed() < debounce) {
+            return;
+        }
+        self.last_alert = Some(Instant::now());
+
+        if let Some(cmd) = &self.config.alert_command {
+            let summary: Vec<String> = events.iter().map(|e| e.describe()).collect();
+            let _ = Command::new("sh")
+                .args(["-c", cmd])
+                .env("AMPWATCH_EVENTS", summary.join("\n"))
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn();
+        } else {
+            let mut stdout = io::stdout();
+            let _ = stdout.write_all(b"\x07");
+            let _ = stdout.flush();
+        }
+    }
+
+    /// Desktop notification for each merged PR and unexpectedly stopped
+    /// agent, if enabled
+    fn notify(&self, events: &[events::Event]) {
+        if !self.config.desktop_notifications {
+            return;
+        }
+        for event in events {
+            match event {
+                events::Event::PrMerged {
+                    repo,
+                    number,
+                    title,
+                } => notify::send(format!("{}: PR #{} merged", repo, number), title.clone()),
+                events::Event::AgentStopped { .. } => {
+                    notify::send("Agent stopped".to_string(), event.describe())
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Indices into `instances` of the instances passing the active filters
+    fn visible_instances(&self) -> Vec<usize> {
+        self.instances
+            .iter()
+            .enumerate()
+            .filter(|(_, inst)| self.instance_filter.matches(inst))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Move the cursors back onto the same instance, PR, and agent after a
+    /// refresh, falling back to the nearest valid index if one disappeared
+    fn reselect(
+        &mut self,
+        instance_id: Option<String>,
+        pr_number: Option<u32>,
+        agent_name: Option<String>,
+    ) {
+        let visible = self.visible_instances();
+        if let Some(pos) = visible
+            .iter()
+            .position(|&i| Some(&self.instances[i].id) == instance_id.as_ref())
+        {
+            self.selected_instance = pos;
+            self.instance_list_state.select(Some(pos));
+        }
+        self.clamp_selected_instance();
+
+        let prs = self.displayed_prs();
+        let pr_idx = prs
+            .iter()
+            .position(|pr| Some(pr.number) == pr_number)
+            .or_else(|| nearest_index(self.pr_list_state.selected(), prs.len()));
+        self.pr_list_state.select(pr_idx.or(Some(0)));
+
+        let agents = self.displayed_agents();
+        let agent_idx = agents
+            .iter()
+            .position(|a| Some(&a.name) == agent_name.as_ref())
+            .or_else(|| nearest_index(self.agent_list_state.selected(), agents.len()));
+        self.agent_list_state.select(agent_idx.or(Some(0)));
+    }
+
+    fn clamp_selected_instance(&mut self) {
+        let visible = self.visible_instances().len();
+        if self.selected_instance >= visible {
+            self.selected_instance = visible.saturating_sub(1);
+            self.instance_list_state
+                .select(Some(self.selected_instance));
+        }
+    }
+
+    fn current_instance_index(&self) -> Option<usize> {
+        self.visible_instances()
+            .get(self.selected_instance)
+            .copied()
+    }
+
+    fn current_instance(&self) -> Option<&Instance> {
+        self.instances.get(self.current_instance_index()?)
+    }
+
+    fn toggle_open_pr_filter(&mut self) {
+        self.instance_filter.only_open_prs = !self.instance_filter.only_open_prs;
+        self.clamp_selected_instance();
+        self.pr_list_state.select(Some(0));
+    }
+
+    /// Agents for the Agents tab, without stopped ones if hidden
+    fn displayed_agents(&self) -> Vec<&Agent> {
+        let Some(instance) = self.current_instance() else {
+            return Vec::new();
+        };
+        instance
+            .agents
+            .iter()
+            .filter(|a| !self.hide_stopped_agents || a.is_running)
+            .collect()
+    }
+
+    fn selected_agent(&self) -> Option<&Agent> {
+        let idx = self.agent_list_state.selected()?;
+        self.displayed_agents().get(idx).copied()
+    }
+
+    fn toggle_hide_stopped_agents(&mut self) {
+        let selected = self.selected_agent().map(|a| a.name.clone());
+        self.hide_stopped_agents = !self.hide_stopped_agents;
+
+        // Stay on the same agent if it's still shown
+        let idx = self
+            .displayed_agents()
+            .iter()
+            .position(|a| Some(&a.name) == selected.as_ref());
+        self.agent_list_state.select(Some(idx.unwrap_or(0)));
+    }
+
+    fn pinned_prs(&self) -> BTreeSet<u32> {
+        self.current_instance()
+            .map(|i| self.state.pinned(&i.state_key()))
+            .unwrap_or_default()
+    }
+
+    /// PRs for the current tab in display order, pinned PRs first
+    fn displayed_prs(&self) -> Vec<&PullRequest> {
+        let Some(instance) = self.current_instance() else {
+            return Vec::new();
+        };
+        let mut prs: Vec<&PullRequest> = match self.selected_tab {
+            1 => instance.open_prs.iter().collect(),
+            2 => instance.merged_prs.iter().collect(),
+            3 => instance.closed_prs.iter().collect(),
+            _ => return Vec::new(),
+        };
+        if let Some(author) = &self.author_filter {
+            prs.retain(|pr| &pr.author.login == author);
+        }
+        if self.hide_drafts {
+            prs.retain(|pr| !pr.is_draft);
+        }
+        if !self.search_query.is_empty() {
+            let query = self.search_query.to_lowercase();
+            prs.retain(|pr| {
+                pr.title.to_lowercase().contains(&query)
+                    || pr.head_ref_name.to_lowercase().contains(&query)
+            });
+        }
+        sort_prs(
+            &mut prs,
+            self.sort_mode,
+            &self.pinned_prs(),
+            self.grouping_by_author(),
+        );
+        prs
+    }
+
+    /// Grouping only applies to the open list
+    fn grouping_by_author(&self) -> bool {
+        self.group_by_author && self.selected_tab == 1
+    }
+
+    fn toggle_group_by_author(&mut self) {
+        let selected = self.selected_pr().map(|pr| pr.number);
+        self.group_by_author = !self.group_by_author;
+        let idx = self
+            .displayed_prs()
+            .iter()
+            .position(|pr| Some(pr.number) == selected);
+        self.pr_list_state.select(Some(idx.unwrap_or(0)));
+    }
+
+    /// Switch between the dark and light palettes, keeping `[theme]` overrides
+    fn toggle_theme(&mut self) {
+        let preset = match self.theme_preset {
+            ThemePreset::Dark => ThemePreset::Light,
+            ThemePreset::Light => ThemePreset::Dark,
+        };
+        self.set_theme(preset);
+    }
+
+    fn set_theme(&mut self, preset: ThemePreset) {
+        self.theme_preset = preset;
+        self.theme = Theme::load(preset, &self.config.theme);
+    }
+
+    fn cycle_sort_mode(&mut self) {
+        let selected = self.selected_pr().map(|pr| pr.number);
+        self.sort_mode = self.sort_mode.next();
+        let idx = self
+            .displayed_prs()
+            .iter()
+            .position(|pr| Some(pr.number) == selected);
+        self.pr_list_state.select(Some(idx.unwrap_or(0)));
+    }
+
+    fn toggle_drafts(&mut self) {
+        let selected = self.selected_pr().map(|pr| pr.number);
+        self.hide_drafts = !self.hide_drafts;
+        let idx = self
+            .displayed_prs()
+            .iter()
+            .position(|pr| Some(pr.number) == selected);
+        self.pr_list_state.select(Some(idx.unwrap_or(0)));
+    }
+
+    /// Block title for the PR list, including any active filter
+    fn pr_list_title(&self) -> String {
+        let mut title = match self.selected_tab {
+            1 => "Open Pull Requests",
+            2 => "Merged Pull Requests",
+            _ => "Closed (Unmerged) Pull Requests",
+        }
+        .to_string();
+        title.push_str(&format!(" [{}]", self.sort_mode.label()));
+        if let Some(author) = &self.author_filter {
+            title.push_str(&format!(" @{}", author));
+        }
+        if self.hide_drafts {
+            title.push_str(" (drafts hidden)");
+        }
+        if self.grouping_by_author() {
+            title.push_str(" by author");
+        }
+        if self.searching || !self.search_query.is_empty() {
+            title.push_str(&format!(" /{}", self.search_query));
+            if self.searching {
+                title.push('_');
+            }
+        }
+        title
+    }
+
+    /// Cycle the author filter through every PR author in the current
+    /// instance, then back to showing all authors
+    fn cycle_author_filter(&mut self) {
+        let Some(instance) = self.current_instance() else {
+            return;
+        };
+        let authors: BTreeSet<&str> = instance
+            .open_prs
+            .iter()
+            .chain(&instance.merged_prs)
+            .chain(&instance.closed_prs)
+            .map(|pr| pr.author.login.as_str())
+            .collect();
+        let next = match &self.author_filter {
+            None => authors.first(),
+            Some(current) => authors
+                .range::<str, _>((Excluded(current.as_str()), Unbounded))
+                .next(),
+        }
+        .map(|login| login.to_string());
+
+        let selected = self.selected_pr().map(|pr| pr.number);
+        self.author_filter = next;
+        match &self.author_filter {
+            Some(author) => self.show_toast(format!("Showing PRs by {}", author)),
+            None => self.show_toast("Showing PRs by all authors"),
+        }
+
+        // Stay on the same PR if it's still shown
+        let idx = self
+            .displayed_prs()
+            .iter()
+            .position(|pr| Some(pr.number) == selected);
+        self.pr_list_state.select(Some(idx.unwrap_or(0)));
+    }
+
+    /// Handle a key while typing a search query. The list filters live;
+    /// Enter keeps the query and Esc clears it.
+    fn search_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Esc => {
+                self.searching = false;
+                self.search_query.clear();
+            }
+            KeyCode::Enter => self.searching = false,
+            KeyCode::Backspace => {
+                self.search_query.pop();
+            }
+            KeyCode::Char(c) => self.search_query.push(c),
+            _ => return,
+        }
+        self.pr_list_state.select(Some(0));
+    }
+
+    fn selected_pr(&self) -> Option<&PullRequest> {
+        let idx = self.pr_list_state.selected()?;
+        self.displayed_prs().get(idx).copied()
+    }
+
+    fn toggle_pin(&mut self) {
+        let Some(number) = self.selected_pr().map(|pr| pr.number) else {
+            return;
+        };
+        let Some(key) = self.current_instance().map(|i| i.state_key()) else {
+            return;
+        };
+        self.state.toggle_pin(&key, number);
+        self.state.save();
+
+        // Keep the cursor on the PR that was just (un)pinned
+        let idx = self
+            .displayed_prs()
+            .iter()
+            .position(|pr| pr.number == number);
+        self.pr_list_state.select(idx);
+    }
+
+    /// Summarize the selected PR with amp, reusing a cached summary of the
+    /// same head commit unless `force` is set
+    fn summarize_pr(&mut self, force: bool) {
+        let pr = match self.selected_pr() {
+            Some(pr) => pr.clone(),
+            None => return,
+        };
+        let pr_number = pr.number;
+        let repo_path = match self.current_instance().and_then(|i| i.repo_path.clone()) {
+            Some(p) => p,
+            None => return,
+        };
+        let cache_key = (repo_path.clone(), pr_number, pr.head_key().to_string());
+
+        if !force {
+            let cached = self.summary_cache.lock().unwrap().get(&cache_key).cloned();
+            if let Some(summary) = cached {
+                self.open_modal("PR Summary (cached, S: re-summarize)", summary, false);
+                self.summary_pr = Some(pr);
+                return;
+            }
+        }
+
+        self.open_modal(
+            "PR Summary",
+            format!(
+                "Loading summary for PR #{}...\n\nPlease wait, amp is analyzing the PR.",
+                pr_number
+            ),
+            true,
+        );
+        let repo_name = self
+            .current_instance()
+            .map(|i| i.repo_name())
+            .unwrap_or_default();
+        let prompt = self.config.render_summary_prompt(&pr, &repo_name);
+        self.summary_pr = Some(pr);
+
+        let repo = repo_path;
+        let job = self.modal_job();
+        let cache = Arc::clone(&self.summary_cache);
+        // A fresh slot per run, so a cancelled run can't take a newer one's child
+        let process: Arc<Mutex<Option<Child>>> = Arc::default();
+        self.modal_process = Arc::clone(&process);
+        let amp = self.config.amp_bin.clone();
+        let timeout = self.config.summary_timeout();
+
+        thread::spawn(move || {
+            // Replace the placeholder with the first line, then append as amp writes
+            let mut streamed = false;
+            let run = run_amp(&amp, &prompt, &repo, &process, timeout, |line| {
+                let Some(mut content) = job.content() else {
+                    return;
+                };
+                if !streamed {
+                    content.clear();
+                    streamed = true;
+                }
+                content.push_str(line);
+                content.push('\n');
+            });
+            match run {
+                AmpRun::Finished(summary) => cache_summary(&cache, cache_key, summary),
+                AmpRun::Failed(stderr) => {
+                    let error = format!("Error summarizing PR:\n{}", stderr);
+                    if streamed {
+                        job.push_str(&format!("\n{}", error));
+                    } else {
+                        job.set(error);
+                    }
+                }
+                AmpRun::NotStarted(e) => job.set(format!("Failed to run amp: {}", e)),
+                AmpRun::TimedOut => job.set(format!(
+                    "amp didn't finish within {}s and was stopped. Press S to try again.",
+                    timeout.as_secs()
+                )),
+                // Cancelling takes (and reaps) the child, so there's nothing left to report
+                AmpRun::Cancelled => return,
+            }
+            job.finish();
+        });
+    }
+
+    /// Ask amp which open PR to review first, given their size, CI, and
+    /// review state. Cached until the set of open PRs changes.
+    fn recommend_review_order(&mut self) {
+        let Some(instance) = self.current_instance() else {
+            return;
+        };
+        let Some(repo) = instance.repo_path.clone() else {
+            self.show_toast("No repo path known for this instance");
+            return;
+        };
+        let mut prs: Vec<&PullRequest> = instance.open_prs.iter().collect();
+        if prs.is_empty() {
+            self.show_toast("No open PRs to rank");
+            return;
+        }
+        prs.sort_by_key(|pr| pr.number);
+        let key = (repo.clone(), prs.iter().map(|pr| pr.number).collect());
+        let prompt = review_order_prompt(&instance.repo_name(), &prs);
+
+        let cached = self.recommendation_cache.lock().unwrap().get(&key).cloned();
+        if let Some(ranking) = cached {
+            self.open_modal("Review Order (cached)", ranking, false);
+            return;
+        }
+        self.open_modal(
+            "Review Order",
+            format!("Asking amp to rank {} open PRs...", prs.len()),
+            true,
+        );
+
+        let job = self.modal_job();
+        let cache = Arc::clone(&self.recommendation_cache);
+        let process: Arc<Mutex<Option<Child>>> = Arc::default();
+        self.modal_process = Arc::clone(&process);
+        let amp = self.config.amp_bin.clone();
+        let timeout = self.config.summary_timeout();
+
+        thread::spawn(move || {
+            let mut streamed = false;
+            let run = run_amp(&amp, &prompt, &repo, &process, timeout, |line| {
+                let Some(mut content) = job.content() else {
+                    return;
+                };
+                if !streamed {
+                    content.clear();
+                    streamed = true;
+                }
+                content.push_str(line);
+                content.push('\n');
+            });
+            job.set(match run {
+                AmpRun::Finished(ranking) => {
+                    cache.lock().unwrap().insert(key, ranking.clone());
+                    ranking
+                }
+                AmpRun::Failed(stderr) => format!("Error ranking PRs:\n{}", stderr),
+                AmpRun::NotStarted(e) => format!("Failed to run amp: {}", e),
+                AmpRun::TimedOut => format!(
+                    "amp didn't finish within {}s and was stopped.",
+                    timeout.as_secs()
+                ),
+                AmpRun::Cancelled => return,
+            });
+            job.finish();
+        });
+    }
+
+    /// Summarize every open PR of the current instance in turn, collecting
+    /// the summaries in the modal. Cached summaries are reused and new ones
+    /// cached; Esc stops the batch.
+    fn summarize_all_prs(&mut self) {
+        let Some(instance) = self.current_instance() else {
+            return;
+        };
+        let Some(repo) = instance.repo_path.clone() else {
+            self.show_toast("No repo path known for this instance");
+            return;
+        };
+        let repo_name = instance.repo_name();
+        let mut prs = instance.open_prs.clone();
+        if prs.is_empty() {
+            self.show_toast("No open PRs to summarize");
+            return;
+        }
+        prs.sort_by_key(|pr| pr.number);
+        let jobs: Vec<(PullRequest, String)> = prs
+            .into_iter()
+            .map(|pr| {
+                let prompt = self.config.render_summary_prompt(&pr, &repo_name);
+                (pr, prompt)
+            })
+            .collect();
+
+        self.open_modal(format!("Summaries of {}", repo_name), "", true);
+        let job = self.modal_job();
+        let cache = Arc::clone(&self.summary_cache);
+        let process: Arc<Mutex<Option<Child>>> = Arc::default();
+        self.modal_process = Arc::clone(&process);
+        let amp = self.config.amp_bin.clone();
+        let timeout = self.config.summary_timeout();
+
+        thread::spawn(move || {
+            let mut done = String::new();
+            for (i, (pr, prompt)) in jobs.iter().enumerate() {
+                // Esc between two runs leaves no child to kill
+                if !job.is_current() {
+                    return;
+                }
+                job.set(format!(
+                    "{}Summarizing {}/{}: #{} {}…",
+                    done,
+                    i + 1,
+                    jobs.len(),
+                    pr.number,
+                    pr.title
+                ));
+
+                let key = (repo.clone(), pr.number, pr.head_key().to_string());
+                let cached = cache.lock().unwrap().get(&key).cloned();
+                let summary = match cached {
+                    Some(summary) => summary,
+                    None => match run_amp(&amp, prompt, &repo, &process, timeout, |_| {}) {
+                        AmpRun::Finished(summary) => {
+                            cache_summary(&cache, key, summary.clone());
+                            summary
+                        }
+                        AmpRun::Failed(stderr) => format!("Error summarizing PR:\n{}", stderr),
+                        AmpRun::TimedOut => {
+                            format!("amp didn't finish within {}s.", timeout.as_secs())
+                        }
+                        AmpRun::NotStarted(e) => {
+                            job.set(format!("Failed to run amp: {}", e));
+                            job.finish();
+                            return;
+                        }
+                        AmpRun::Cancelled => return,
+                    },
+                };
+                done.push_str(&format!(
+                    "## #{} {}\n\n{}\n\n",
+                    pr.number,
+                    pr.title,
+                    summary.trim()
+                ));
+            }
+            job.set(done);
+            job.finish();
+        });
+    }
+
+    /// Jump to the Open PRs tab and summarize the most recently created PR
+    /// among those the search and filters leave shown
+    fn summarize_newest_pr(&mut self) {
+        self.select_tab(1);
+        let newest = self
+            .displayed_prs()
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, pr)| pr.created())
+            .map(|(idx, _)| idx);
+        let Some(idx) = newest else {
+            let filtered = self
+                .current_instance()
+                .is_some_and(|i| !i.open_prs.is_empty());
+            self.show_toast(if filtered {
+                "No open PRs match the current search and filters"
+            } else {
+                "No open PRs to summarize"
+            });
+            return;
+        };
+        self.pr_list_state.select(Some(idx));
+        self.summarize_pr(false);
+    }
+
+    fn show_gh_log(&mut self) {
+        let Some(instance) = self.current_instance() else {
+            return;
+        };
+
+        let content = if instance.gh_log.is_empty() {
+            match &instance.repo_path {
+                Some(path) => format!("No gh commands have run yet for {}.", path),
+                None => "No gh commands ran: repo path unknown (no running agent session to read it from).".to_string(),
+            }
+        } else {
+            instance
+                .gh_log
+                .iter()
+                .map(|log| {
+                    format!(
+                        "$ {}\n(cwd: {})\nstatus: {}\n\n--- stdout ---\n{}\n--- stderr ---\n{}\n",
+                        log.command,
+                        instance.repo_path.as_deref().unwrap_or("?"),
+                        log.status,
+                        log.stdout.trim_end(),
+                        log.stderr.trim_end(),
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        let title = format!("gh output: {}", instance.repo_name());
+        self.open_modal(title, content, false);
+    }
+
+    /// Ask before checking out the selected PR, since it changes the
+    /// instance's working tree
+    fn confirm_checkout(&mut self) {
+        let Some(pr) = self.selected_pr().cloned() else {
+            return;
+        };
+        let Some(repo_path) = self.current_instance().and_then(|i| i.repo_path.clone()) else {
+            self.show_toast("No repo path known for this instance");
+            return;
+        };
+
+        self.open_modal(
+            "Checkout",
+            format!(
+                "Check out PR #{} in {}?\n\nThe working tree will switch to branch '{}'.",
+                pr.number, repo_path, pr.head_ref_name
+            ),
+            false,
+        );
+        self.pending_action = Some(PendingAction::Checkout {
+            number: pr.number,
+            branch: pr.head_ref_name,
+        });
+    }
+
+    /// Ask before merging the selected open PR, warning if it isn't approved
+    fn confirm_merge(&mut self) {
+        let Some(pr) = self.selected_pr().cloned() else {
+            return;
+        };
+        if self
+            .current_instance()
+            .and_then(|i| i.repo_path.as_ref())
+            .is_none()
+        {
+            self.show_toast("No repo path known for this instance");
+            return;
+        }
+
+        let approved = pr.review_decision == "APPROVED";
+        let mut message = format!(
+            "Merge PR #{}?\n\n{}\n\nBranch '{}' will be {}.",
+            pr.number,
+            pr.title,
+            pr.head_ref_name,
+            self.config.merge_method.describe()
+        );
+        if !approved {
+            let decision = if pr.review_decision.is_empty() {
+                "no review"
+            } else {
+                pr.review_decision.as_str()
+            };
+            message.push_str(&format!(
+                "\n\n⚠ This PR is not approved ({}). Press Y to merge anyway.",
+                decision
+            ));
+        }
+        self.open_modal("Merge", message, false);
+        self.pending_action = Some(PendingAction::Merge {
+            number: pr.number,
+            approved,
+        });
+    }
+
+    /// Run the confirmed action
+    fn run_pending_action(&mut self) {
+        match self.pending_action.take() {
+            Some(PendingAction::Checkout { number, branch }) => self.checkout_pr(number, branch),
+            Some(PendingAction::Merge { number, .. }) => self.merge_pr(number),
+            // The event loop breaks on a confirmed quit before getting here
+            Some(PendingAction::Quit) | None => {}
+        }
+    }
+
+    /// Whether `q` should quit now. With `confirm_quit` set and agents still
+    /// running, asks in the modal first.
+    fn confirm_quit(&mut self) -> bool {
+        if !self.config.confirm_quit {
+            return true;
+        }
+        let running: Vec<usize> = self
+            .instances
+            .iter()
+            .map(|i| i.running_agent_count())
+            .filter(|&n| n > 0)
+            .collect();
+        if running.is_empty() {
+            return true;
+        }
+        let agents: usize = running.iter().sum();
+        let message = format!(
+            "{} agent{} running across {} instance{} — quit? (y/n)\n\nPress q again to quit.",
+            agents,
+            if agents == 1 { "" } else { "s" },
+            running.len(),
+            if running.len() == 1 { "" } else { "s" }
+        );
+        self.open_modal("Quit", message, false);
+        self.pending_action = Some(PendingAction::Quit);
+        false
+    }
+
+    /// Merge so the PR moves to the merged list on the next refresh
+    fn merge_pr(&mut self, number: u32) {
+        let number = number.to_string();
+        let flag = self.config.merge_method.flag();
+        let done = format!("Merged PR #{}", number);
+        self.run_gh("Merge", &["pr", "merge", &number, flag], done);
+    }
+
+    /// Fetch another page of closed and merged PRs for the current instance
+    fn load_more_closed_prs(&mut self) {
+        let Some(instance) = self.current_instance() else {
+            return;
+        };
+        let step = self.config.merged_pr_limit.max(1);
+        let id = instance.id.clone();
+        let limit = instance.merged_pr_limit + step;
+        self.merged_pr_limits.insert(id, limit);
+        self.show_toast(format!("Loading up to {} merged PRs…", limit));
+        self.refresh_requested.store(true, Ordering::Relaxed);
+    }
+
+    /// Ask for an optional comment, then approve the selected open PR
+    fn prompt_approve(&mut self) {
+        let Some(pr) = self.selected_pr() else {
+            return;
+        };
+        self.text_input = Some(TextInput {
+            prompt: format!("Approve PR #{} with comment (optional)", pr.number),
+            value: String::new(),
+            action: InputAction::Approve { number: pr.number },
+        });
+    }
+
+    fn prompt_send_keys(&mut self) {
+        let Some(agent) = self.selected_agent() else {
+            return;
+        };
+        if !agent.is_running {
+            self.show_toast(format!("{} isn't running", agent.name));
+            return;
+        }
+        self.text_input = Some(TextInput {
+            prompt: format!("Send to {}", agent.name),
+            value: String::new(),
+            action: InputAction::SendKeys {
+                agent: agent.name.clone(),
+            },
+        });
+    }
+
+    fn input_key(&mut self, code: KeyCode) {
+        let Some(input) = &mut self.text_input else {
+            return;
+        };
+        match code {
+            KeyCode::Esc => self.text_input = None,
+            KeyCode::Enter => {
+                if let Some(input) = self.text_input.take() {
+                    self.submit_input(input);
+                }
+            }
+            KeyCode::Backspace => {
+                input.value.pop();
+            }
+            KeyCode::Char(c) => input.value.push(c),
+            _ => {}
+        }
+    }
+
+    fn submit_input(&mut self, input: TextInput) {
+        let comment = input.value.trim();
+        match input.action {
+            InputAction::Approve { number } => {
+                let number = number.to_string();
+                let mut args = vec!["pr", "review", &number, "--approve"];
+                if !comment.is_empty() {
+                    args.extend(["--body", comment]);
+                }
+                let done = format!("Approved PR #{}", number);
+                self.run_gh("Approve", &args, done);
+            }
+            InputAction::SendKeys { agent } => {
+                let agent = self
+                    .current_instance()
+                    .and_then(|i| i.agents.iter().find(|a| a.name == agent));
+                let Some(agent) = agent else {
+                    return;
+                };
+                let message = match agent.send_keys(&self.runner, &input.value) {
+                    Ok(()) => format!("Sent '{}' to {}", input.value, agent.name),
+                    Err(e) => format!("Couldn't send to {}: {}", agent.name, e),
+                };
+                self.show_toast(message);
+            }
+        }
+    }
+
+    /// Run a gh command that changes a PR in the current repo, streaming
+    /// its output into the modal. A refresh follows if it succeeds.
+    fn run_gh(&mut self, title: &str, args: &[&str], done: String) {
+        let Some(repo_path) = self.current_instance().and_then(|i| i.repo_path.clone()) else {
+            self.show_toast("No repo path known for this instance");
+            return;
+        };
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        self.open_modal(title, format!("$ gh {}\n", args.join(" ")), true);
+
+        let job = self.modal_job();
+        let process: Arc<Mutex<Option<Child>>> = Arc::default();
+        self.modal_process = Arc::clone(&process);
+        let refresh_requested = Arc::clone(&self.refresh_requested);
+        let gh = self.config.gh_bin.clone();
+
+        thread::spawn(move || {
+            let mut cmd = Command::new(gh);
+            cmd.args(&args).current_dir(&repo_path).stdin(Stdio::null());
+            let result = match stream_output(&mut cmd, &job, &process) {
+                Ok(Some(status)) if status.success() => {
+                    refresh_requested.store(true, Ordering::Relaxed);
+                    format!("\n✓ {}", done)
+                }
+                Ok(Some(status)) => format!("\n✗ gh failed ({})", status),
+                // Killed by closing the modal
+                Ok(None) => return,
+                Err(e) => format!("\nFailed to run gh: {}", e),
+            };
+            job.push_str(&result);
+            job.finish();
+        });
+    }
+
+    /// Check out the confirmed PR so the working tree follows it
+    fn checkout_pr(&mut self, number: u32, branch: String) {
+        let number = number.to_string();
+        let done = format!("Now on branch '{}'", branch);
+        self.run_gh("Checkout", &["pr", "checkout", &number], done);
+    }
+
+    fn open_checklist(&mut self) {
+        if self.selected_pr().is_some() && !self.config.checklist.is_empty() {
+            self.show_checklist = true;
+            self.checklist_cursor = 0;
+        }
+    }
+
+    fn toggle_checklist_item(&mut self, index: usize) {
+        let Some(item) = self.config.checklist.get(index).cloned() else {
+            return;
+        };
+        let Some(number) = self.selected_pr().map(|pr| pr.number) else {
+            return;
+        };
+        let Some(repo) = self.current_instance().map(|i| i.state_key()) else {
+            return;
+        };
+        self.state.toggle_checklist_item(&repo, number, &item);
+        self.state.save();
+    }
+
+    /// Open the current instance's log directory in the system file manager
+    fn open_logs_dir(&mut self) {
+        let Some(logs_dir) = self.current_instance().and_then(|i| i.logs_dir.clone()) else {
+            self.show_toast("No log directory found for this instance");
+            return;
+        };
+
+        let opener = if cfg!(target_os = "macos") {
+            "open"
+        } else if cfg!(target_os = "windows") {
+            "explorer"
+        } else {
+            "xdg-open"
+        };
+        let spawned = Command::new(opener)
+            .arg(&logs_dir)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+
+        match spawned {
+            Ok(mut child) => {
+                // Reap the opener so it doesn't linger as a zombie
+                thread::spawn(move || child.wait());
+                self.show_toast(format!("Opened {}", logs_dir));
+            }
+            Err(_) => self.show_toast(format!("Logs: {}", logs_dir)),
+        }
+    }
+
+    /// Write the current instance to `~/ampwatch-{repo}-{timestamp}.json`
+    fn export_instance(&mut self) {
+        let Some(instance) = self.current_instance() else {
+            return;
+        };
+        let repo = instance.repo_name();
+        let now = Utc::now();
+        let snapshot = serde_json::json!({
+            "repo": repo,
+            "exported_at": now.to_rfc3339(),
+            "instance": instance,
+        });
+        let Some(home) = dirs::home_dir() else {
+            self.show_toast("Export failed: home directory unknown");
+            return;
+        };
+        let path = home.join(format!(
+            "ampwatch-{}-{}.json",
+            repo,
+            now.format("%Y%m%d-%H%M%S")
+        ));
+
+        let written = serde_json::to_string_pretty(&snapshot)
+            .map_err(io::Error::from)
+            .and_then(|json| std::fs::write(&path, json));
+        match written {
+            Ok(()) => self.show_toast(format!("Exported to {}", path.display())),
+            Err(e) => self.show_toast(format!("Export failed: {}", e)),
+        }
+    }
+
+    /// Open the selected PR with `gh pr view --web`
+    fn open_pr_in_browser(&mut self) {
+        let Some(number) = self.selected_pr().map(|pr| pr.number) else {
+            return;
+        };
+        let Some(repo_path) = self.current_instance().and_then(|i| i.repo_path.clone()) else {
+            self.show_toast("No repo path known for this instance");
+            return;
+        };
+
+        let spawned = Command::new(&self.config.gh_bin)
+            .args(["pr", "view", &number.to_string(), "--web"])
+            .current_dir(&repo_path)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+
+        match spawned {
+            Ok(mut child) => {
+                thread::spawn(move || child.wait());
+                self.show_toast(format!("Opening PR #{} in browser", number));
+            }
+            Err(e) => self.show_toast(format!("Failed to run gh: {}", e)),
+        }
+    }
+
+    /// Restart the selected agent, explaining in a modal when ampwatch
+    /// doesn't know how amptown launched it
+    fn restart_agent(&mut self) {
+        let Some(agent) = self.selected_agent().cloned() else {
+            return;
+        };
+        let repo_path = self.current_instance().and_then(|i| i.repo_path.clone());
+
+        let problem = match (&agent.launch_cmd, &repo_path) {
+            (None, _) => Some(format!(
+                "ampwatch can't restart {} because its launch command is unknown.\n\n\
+                 The command is rebuilt from {}-launcher.sh in the instance's log \
+                 directory, which wasn't found. Restart the instance with amptown instead.",
+                agent.name, agent.name
+            )),
+            (Some(_), None) => Some(format!(
+                "ampwatch can't restart {} because the instance's repo path is unknown.\n\n\
+                 The repo path is read from a running agent's tmux pane; with every agent \
+                 stopped there is none to read it from. Restart the instance with amptown instead.",
+                agent.name
+            )),
+            _ => None,
+        };
+        if let Some(problem) = problem {
+            self.open_modal(format!("Restart {}", agent.name), problem, false);
+            return;
+        }
+
+        match agent.restart(&self.runner, repo_path.as_deref().unwrap_or_default()) {
+            Ok(()) => {
+                self.show_toast(format!("Restarted {}", agent.name));
+                self.refresh();
+            }
+            Err(e) => self.show_toast(format!("Failed to restart {}: {}", agent.name, e)),
+        }
+    }
+
+    /// Show the tail of the selected agent's log in the modal, scrolled to
+    /// the newest line
+    fn view_agent_log(&mut self) {
+        let Some(agent) = self.selected_agent().cloned() else {
+            return;
+        };
+        let Some(logs_dir) = self.current_instance().and_then(|i| i.logs_dir.clone()) else {
+            self.show_toast("No log directory found for this instance");
+            return;
+        };
+        let path = agent.log_path(&logs_dir);
+
+        self.open_modal(
+            format!("{} log", agent.name),
+            format!("Reading {}...", path),
+            true,
+        );
+        self.modal_scroll_to_end = true;
+
+        let job = self.modal_job();
+        thread::spawn(move || {
+            let text = match File::open(&path) {
+                Ok(file) => {
+                    let mut tail = VecDeque::with_capacity(LOG_VIEW_LINES);
+                    for line in BufReader::new(file).lines().map_while(Result::ok) {
+                        if tail.len() == LOG_VIEW_LINES {
+                            tail.pop_front();
+                        }
+                        tail.push_back(line);
+                    }
+                    Vec::from(tail).join("\n")
+                }
+                Err(e) => format!("Failed to read {}: {}", path, e),
+            };
+            job.set(text);
+            job.finish();
+        });
+    }
+
+    /// Capture the selected agent's tmux pane for the detail panel on a
+    /// background thread; `poll_pane_preview` applies it. Only running
+    /// agents on the Agents tab are captured, as soon as one is selected
+    /// and then every `PANE_PREVIEW_INTERVAL`.
+    fn refresh_pane_preview(&mut self) {
+        let Some(agent) = self
+            .selected_agent()
+            .filter(|a| self.selected_tab == 0 && a.is_running)
+            .cloned()
+        else {
+            self.pane_preview = None;
+            return;
+        };
+        if self.pending_pane_preview.is_some() {
+            return;
+        }
+        let session = agent.session_name();
+        let selection_changed = self
+            .pane_preview
+            .as_ref()
+            .is_none_or(|(shown, _)| *shown != session);
+        if !selection_changed && self.last_pane_capture.elapsed() < PANE_PREVIEW_INTERVAL {
+            return;
+        }
+
+        let (tx, rx) = mpsc::channel();
+        let runner = self.runner.clone();
+        thread::spawn(move || {
+            let _ = tx.send((session, agent.capture_pane(&runner, PANE_PREVIEW_LINES)));
+        });
+        self.pending_pane_preview = Some(rx);
+        self.last_pane_capture = Instant::now();
+    }
+
+    /// Apply a finished pane capture, if any
+    fn poll_pane_preview(&mut self) {
+        let Some(rx) = &self.pending_pane_preview else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(capture) => {
+                self.pending_pane_preview = None;
+                self.pane_preview = Some(capture);
+            }
+            Err(mpsc::TryRecvError::Empty) => {}
+            Err(mpsc::TryRecvError::Disconnected) => self.pending_pane_preview = None,
+        }
+    }
+
+    /// tmux session of the selected agent, if it is running
+    fn attachable_session(&mut self) -> Option<String> {
+        let agent = self.selected_agent()?;
+        if agent.is_running {
+            Some(agent.session_name())
+        } else {
+            let message = format!("{} is not running; nothing to attach to", agent.name);
+            self.show_toast(message);
+            None
+        }
+    }
+
+    /// Show the modal with fresh content, scrolled to the top. Callers set
+    /// `summary_pr` or `pending_action` afterwards if the modal needs them.
+    fn open_modal(&mut self, title: impl Into<String>, content: impl Into<String>, loading: bool) {
+        self.show_modal = true;
+        self.modal_title = title.into();
+        self.modal_scroll = 0;
+        self.modal_scroll_to_end = false;
+        self.modal_footer = None;
+        self.summary_pr = None;
+        self.pending_action = None;
+        let mut shown = self.modal_content.lock().unwrap();
+        self.modal_generation.fetch_add(1, Ordering::Relaxed);
+        *shown = content.into();
+        *self.modal_loading.lock().unwrap() = loading;
+    }
+
+    /// Handle for a background job writing into the modal just opened
+    fn modal_job(&self) -> ModalJob {
+        ModalJob {
+            content: Arc::clone(&self.modal_content),
+            loading: Arc::clone(&self.modal_loading),
+            generation: Arc::clone(&self.modal_generation),
+            started: self.modal_generation.load(Ordering::Relaxed),
+        }
+    }
+
+    fn scroll_modal(&mut self, lines: i16) {
+        // render_modal clamps the upper end once it knows the wrapped height
+        self.modal_scroll = self.modal_scroll.saturating_add_signed(lines);
+    }
+
+    fn open_help(&mut self) {
+        self.show_help = true;
+        self.help_scroll = 0;
+    }
+
+    fn scroll_help(&mut self, lines: i16) {
+        // render_help clamps the upper end the same way
+        self.help_scroll = self.help_scroll.saturating_add_signed(lines);
+    }
+
+    /// Close the modal, killing the amp or gh process still feeding it
+    fn close_modal(&mut self) {
+        self.show_modal = false;
+        {
+            let _shown = self.modal_content.lock().unwrap();
+            self.modal_generation.fetch_add(1, Ordering::Relaxed);
+        }
+        if let Some(mut child) = self.modal_process.lock().unwrap().take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        *self.modal_loading.lock().unwrap() = false;
+    }
+
+    fn show_toast(&mut self, message: impl Into<String>) {
+        self.toast = Some((message.into(), Instant::now()));
+    }
+
+    fn copy_pr_url(&mut self) {
+        let Some(url) = self.selected_pr().map(|pr| pr.url.clone()) else {
+            return;
+        };
+        if url.is_empty() {
+            self.show_toast("gh didn't report a URL for this PR");
+            return;
+        }
+        self.copy(url.clone(), format!("Copied {}", url));
+    }
+
+    /// Copy the modal's text as shown, e.g. a whole summary
+    fn copy_modal_text(&mut self) {
+        if *self.modal_loading.lock().unwrap() {
+            return;
+        }
+        let text = self.modal_content.lock().unwrap().clone();
+        self.copy(text, "Copied");
+    }
+
+    /// Copy to the clipboard, toasting `done` or why it failed (e.g. no
+    /// display server)
+    fn copy(&mut self, text: String, done: impl Into<String>) {
+        match self.clipboard.copy(text) {
+            Ok(()) => self.show_toast(done),
+            Err(e) => self.show_toast(format!("Clipboard unavailable: {}", e)),
+        }
+    }
+
+    /// Save the summary in the modal to
+    /// `~/ampwatch/summaries/{repo}-pr{number}.md`, replacing an older one
+    fn save_summary(&mut self) {
+        if *self.modal_loading.lock().unwrap() {
+            return;
+        }
+        let (Some(pr), Some(instance)) = (&self.summary_pr, self.current_instance()) else {
+            return;
+        };
+        let Some(home) = dirs::home_dir() else {
+            self.modal_footer = Some("Save failed: home directory unknown".to_string());
+            return;
+        };
+        let repo = instance.repo_name();
+        let dir = home.join("ampwatch").join("summaries");
+        let path = dir.join(format!("{}-pr{}.md", repo, pr.number));
+        let document = format!(
+            "# #{} {}\n\n- Repo: {}\n- PR: {}\n- Saved: {}\n\n{}\n",
+            pr.number,
+            pr.title,
+            repo,
+            pr.url,
+            Utc::now().format("%Y-%m-%d %H:%M UTC"),
+            self.modal_content.lock().unwrap().trim()
+        );
+
+        let written = std::fs::create_dir_all(&dir).and_then(|()| std::fs::write(&path, document));
+        self.modal_footer = Some(match written {
+            Ok(()) => format!("Saved to {}", path.display()),
+            Err(e) => format!("Save failed: {}", e),
+        });
+    }
+
+    /// Copy the summary in the modal as a Markdown quote linking its PR
+    fn copy_summary_quote(&mut self) {
+        if *self.modal_loading.lock().unwrap() {
+            return;
+        }
+        let Some(pr) = &self.summary_pr else {
+            return;
+        };
+        let quote = pr.markdown_quote(&self.modal_content.lock().unwrap());
+        self.copy(quote, "Copied summary as Markdown quote");
+    }
+
+    fn next_tab(&mut self) {
+        self.select_tab(step_forward(
+            self.selected_tab,
+            TAB_COUNT,
+            self.config.wrap_navigation,
+        ));
+    }
+
+    fn prev_tab(&mut self) {
+        self.select_tab(step_back(
+            self.selected_tab,
+            TAB_COUNT,
+            self.config.wrap_navigation,
+        ));
+    }
+
+    fn select_tab(&mut self, tab: usize) {
+        if tab != self.selected_tab {
+            self.selected_tab = tab;
+            self.pr_list_state.select(Some(0));
+        }
+    }
+
+    fn mouse(&mut self, event: MouseEvent) {
+        // Overlays are drawn over the clickable regions
+        let overlay =
+            self.show_modal || self.show_help || self.show_checklist || self.text_input.is_some();
+        match event.kind {
+            MouseEventKind::Down(MouseButton::Left) if !overlay => {
+                self.click(event.column, event.row)
+            }
+            MouseEventKind::ScrollDown if self.show_modal => self.scroll_modal(3),
+            MouseEventKind::ScrollUp if self.show_modal => self.scroll_modal(-3),
+            MouseEventKind::ScrollDown if self.show_help => self.scroll_help(3),
+            MouseEventKind::ScrollUp if self.show_help => self.scroll_help(-3),
+            MouseEventKind::ScrollDown if !overlay => self.scroll_list(1),
+            MouseEventKind::ScrollUp if !overlay => self.scroll_list(-1),
+            _ => {}
+        }
+    }
+
+    /// Move the active list's selection by `delta`, stopping at either end
+    /// instead of wrapping. `isize::MIN` and `MAX` jump to the first and last.
+    fn scroll_list(&mut self, delta: isize) {
+        let (len, state) = self.active_list();
+        if let Some(i) = step_by(state.selected().unwrap_or(0), len, delta) {
+            state.select(Some(i));
+        }
+    }
+
+    /// Select whatever was drawn under a left click
+    fn click(&mut self, column: u16, row: u16) {
+        let position = Position::new(column, row);
+        let Some((area, target)) = self
+            .click_targets
+            .iter()
+            .find(|(area, _)| area.contains(position))
+        else {
+            return;
+        };
+        let line = (row - area.y) as usize;
+        match target {
+            ClickTarget::Tab(tab) => self.select_tab(*tab),
+            ClickTarget::Instance(index) => self.select_instance(*index),
+            ClickTarget::OverviewList { offset } => {
+                let index = offset + line;
+                if index < self.visible_instances().len() {
+                    self.overview_list_state.select(Some(index));
+                }
+            }
+            ClickTarget::PrList { rows, offset } => {
+                if let Some(&Some(index)) = rows.get(offset + line) {
+                    self.pr_list_state.select(Some(index));
+                }
+            }
+            ClickTarget::AgentList { names, offset } => {
+                let Some(name) = names.get(offset + line / 2) else {
+                    return;
+                };
+                let index = self.displayed_agents().iter().position(|a| &a.name == name);
+                if index.is_some() {
+                    self.agent_list_state.select(index);
+                }
+            }
+        }
+    }
 
-    // Refresh
-    last_refresh: Instant,
+    fn next_instance(&mut self) {
+        let len = self.visible_instances().len();
+        if len > 0 {
+            let next = step_forward(self.selected_instance, len, self.config.wrap_navigation);
+            self.select_instance(next);
+        }
+    }
 
-    // Live indicator
-    tick: usize,
-}
+    fn prev_instance(&mut self) {
+        let len = self.visible_instances().len();
+        if len > 0 {
+            let prev = step_back(self.selected_instance, len, self.config.wrap_navigation);
+            self.select_instance(prev);
+        }
+    }
 
-impl App {
-    fn new() -> Self {
-        let mut app = Self {
-            instances: Vec::new(),
-            selected_instance: 0,
-            selected_tab: 0,
-            pr_list_state: ListState::default(),
-            agent_list_state: ListState::default(),
-            instance_list_state: ListState::default(),
-            show_modal: false,
-            modal_content: Arc::new(Mutex::new(String::new())),
-            modal_loading: Arc::new(Mutex::new(false)),
-            last_refresh: Instant::now(),
-            tick: 0,
-        };
-        app.instance_list_state.select(Some(0));
-        app.agent_list_state.select(Some(0));
-        app
+    fn open_overview(&mut self) {
+        self.show_overview = true;
+        self.overview_list_state
+            .select(Some(self.selected_instance));
     }
 
-    fn refresh(&mut self) {
-        // Discover all running instances
-        let discovered = discover_instances();
-        
-        // Convert to vec and sort by repo name for stable ordering
-        let mut instances: Vec<Instance> = discovered.into_values().collect();
-        instances.sort_by_key(|a| a.repo_name());
-        
-        // Refresh each instance's data
-        for instance in &mut instances {
-            instance.refresh();
-        }
-        
-        self.instances = instances;
-        
-        // Ensure selected instance is valid
-        if self.selected_instance >= self.instances.len() {
-            self.selected_instance = self.instances.len().saturating_sub(1);
+    /// Leave the overview for the instance under its cursor
+    fn open_overview_instance(&mut self) {
+        if let Some(index) = self.overview_list_state.selected() {
+            if index < self.visible_instances().len() {
+                self.select_instance(index);
+                self.show_overview = false;
+            }
         }
-        
-        self.last_refresh = Instant::now();
     }
 
-    fn current_instance(&self) -> Option<&Instance> {
-        self.instances.get(self.selected_instance)
+    fn select_instance(&mut self, index: usize) {
+        if index != self.selected_instance {
+            self.selected_instance = index;
+            self.instance_list_state.select(Some(index));
+            self.pr_list_state.select(Some(0));
+            // Authors are per repo
+            self.author_filter = None;
+        }
     }
 
-    fn selected_pr(&self) -> Option<&PullRequest> {
-        let instance = self.current_instance()?;
-        let idx = self.pr_list_state.selected()?;
-        if self.selected_tab == 1 {
-            instance.open_prs.get(idx)
-        } else if self.selected_tab == 2 {
-            instance.closed_prs.get(idx)
+    /// Length of the active list and its selection state
+    fn active_list(&mut self) -> (usize, &mut ListState) {
+        if self.show_overview {
+            let len = self.visible_instances().len();
+            (len, &mut self.overview_list_state)
+        } else if self.selected_tab == 0 {
+            let len = self.displayed_agents().len();
+            (len, &mut self.agent_list_state)
         } else {
-            None
+            let len = self.displayed_prs().len();
+            (len, &mut self.pr_list_state)
         }
     }
 
-    fn summarize_pr(&mut self) {
-        let pr_number = match self.selected_pr() {
-            Some(pr) => pr.number,
-            None => return,
-        };
-        let repo_path = match self.current_instance().and_then(|i| i.repo_path.clone()) {
-            Some(p) => p,
-            None => return,
-        };
+    fn next_item(&mut self) {
+        let wrap = self.config.wrap_navigation;
+        let (len, state) = self.active_list();
+        if len > 0 {
+            let i = state.selected().unwrap_or(0);
+            state.select(Some(step_forward(i, len, wrap)));
+        }
+    }
 
-        self.show_modal = true;
-        *self.modal_loading.lock().unwrap() = true;
-        *self.modal_content.lock().unwrap() = format!(
-            "Loading summary for PR #{}...\n\nPlease wait, amp is analyzing the PR.",
-            pr_number
-        );
+    fn prev_item(&mut self) {
+        let wrap = self.config.wrap_navigation;
+        let (len, state) = self.active_list();
+        if len > 0 {
+            let i = state.selected().unwrap_or(0);
+            state.select(Some(step_back(i, len, wrap)));
+        }
+    }
 
-        let repo = repo_path;
-        let content = Arc::clone(&self.modal_content);
-        let loading = Arc::clone(&self.modal_loading);
+    /// Items the active list showed when last drawn, for PageUp/PageDown
+    fn page_size(&self) -> isize {
+        let rows = self
+            .click_targets
+            .iter()
+            .find_map(|(area, target)| match target {
+                ClickTarget::OverviewList { .. } | ClickTarget::PrList { .. } => Some(area.height),
+                // Agents take two lines each
+                ClickTarget::AgentList { .. } => Some(area.height / 2),
+                _ => None,
+            });
+        rows.unwrap_or(1).max(1) as isize
+    }
+}
+
+/// How a `run_amp` call ended
+enum AmpRun {
+    /// Exited successfully with this output
+    Finished(String),
+    /// Exited with an error; holds stderr
+    Failed(String),
+    NotStarted(io::Error),
+    /// Killed by the user (Esc)
+    Cancelled,
+    /// Killed after the summary timeout
+    TimedOut,
+}
+
+/// Run amp with `prompt` in `repo` and wait for it to exit. The child is
+/// parked in `process` so Esc can kill it by taking it, and a watcher
+/// thread kills it after `timeout`. `on_line` sees stdout as amp writes it.
+fn run_amp(
+    amp: &str,
+    prompt: &str,
+    repo: &str,
+    process: &Arc<Mutex<Option<Child>>>,
+    timeout: Duration,
+    mut on_line: impl FnMut(&str),
+) -> AmpRun {
+    let spawned = Command::new(amp)
+        .args(["--dangerously-allow-all", "--no-ide", "-x", prompt])
+        .current_dir(repo)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+    let mut child = match spawned {
+        Ok(child) => child,
+        Err(e) => return AmpRun::NotStarted(e),
+    };
 
+    // Drain stderr alongside stdout so a chatty amp can't block on a full pipe
+    let stderr = child.stderr.take().map(|mut err| {
         thread::spawn(move || {
-            let output = Command::new("amp")
-                .args([
-                    "--dangerously-allow-all",
-                    "--no-ide",
-                    "-x",
-                    &format!(
-                        "Summarize PR #{} in this repository. Include: what changed, why, and any concerns. Be concise.",
-                        pr_number
-                    ),
-                ])
-                .current_dir(&repo)
-                .output();
+            let mut s = String::new();
+            let _ = err.read_to_string(&mut s);
+            s
+        })
+    });
 
-            let result = match output {
-                Ok(out) if out.status.success() => {
-                    String::from_utf8_lossy(&out.stdout).to_string()
-                }
-                Ok(out) => {
-                    format!(
-                        "Error summarizing PR:\n{}",
-                        String::from_utf8_lossy(&out.stderr)
-                    )
-                }
-                Err(e) => {
-                    format!("Failed to run amp: {}", e)
+    let stdout = child.stdout.take();
+    *process.lock().unwrap() = Some(child);
+    let cancelled = || process.lock().unwrap().is_none();
+
+    // Kill a hung amp the same way Esc does, flagging why
+    let timed_out = Arc::new(AtomicBool::new(false));
+    {
+        let process = Arc::clone(process);
+        let timed_out = Arc::clone(&timed_out);
+        thread::spawn(move || {
+            let deadline = Instant::now() + timeout;
+            while Instant::now() < deadline {
+                thread::sleep(Duration::from_millis(200));
+                if process.lock().unwrap().is_none() {
+                    return;
                 }
+            }
+            let Some(mut child) = process.lock().unwrap().take() else {
+                return;
             };
-
-            *content.lock().unwrap() = result;
-            *loading.lock().unwrap() = false;
+            timed_out.store(true, Ordering::Relaxed);
+            let _ = child.kill();
+            let _ = child.wait();
         });
     }
 
-    fn next_tab(&mut self) {
-        self.selected_tab = (self.selected_tab + 1) % 3;
-        self.pr_list_state.select(Some(0));
+    let mut output = String::new();
+    if let Some(out) = stdout {
+        for line in BufReader::new(out).lines().map_while(Result::ok) {
+            if cancelled() {
+                break;
+            }
+            on_line(&line);
+            output.push_str(&line);
+            output.push('\n');
+        }
     }
 
-    fn prev_tab(&mut self) {
-        self.selected_tab = if self.selected_tab == 0 {
-            2
+    let stderr = stderr.and_then(|h| h.join().ok()).unwrap_or_default();
+    let Some(mut child) = process.lock().unwrap().take() else {
+        return if timed_out.load(Ordering::Relaxed) {
+            AmpRun::TimedOut
         } else {
-            self.selected_tab - 1
+            AmpRun::Cancelled
         };
-        self.pr_list_state.select(Some(0));
+    };
+    if child.wait().is_ok_and(|status| status.success()) {
+        AmpRun::Finished(output)
+    } else {
+        AmpRun::Failed(stderr)
     }
+}
 
-    fn next_instance(&mut self) {
-        if !self.instances.is_empty() {
-            self.selected_instance = (self.selected_instance + 1) % self.instances.len();
-            self.instance_list_state.select(Some(self.selected_instance));
-            self.pr_list_state.select(Some(0));
+/// Prompt asking amp to rank `prs` for review, one line of facts per PR
+fn review_order_prompt(repo: &str, prs: &[&PullRequest]) -> String {
+    let mut prompt = format!(
+        "These are the open PRs in {}. Rank them in the order a human should review them, \
+         one line per PR with a short reason. Favor small, green, approved PRs that unblock \
+         others; flag risky ones.\n\n",
+        repo
+    );
+    for pr in prs {
+        let checks = match pr.checks {
+            CheckStatus::Passing => "passing",
+            CheckStatus::Failing => "failing",
+            CheckStatus::Pending => "pending",
+            CheckStatus::None => "none",
+        };
+        let review = if pr.review_decision.is_empty() {
+            "none"
+        } else {
+            pr.review_decision.as_str()
+        };
+        prompt.push_str(&format!(
+            "- #{} {} (branch {}, +{}/-{} in {} files, CI {}, review {}{})\n",
+            pr.number,
+            pr.title,
+            pr.head_ref_name,
+            pr.additions,
+            pr.deletions,
+            pr.changed_files,
+            checks,
+            review,
+            if pr.is_draft { ", draft" } else { "" }
+        ));
+    }
+    prompt
+}
+
+/// Cache a PR's summary, dropping those of its older commits
+fn cache_summary(cache: &SummaryCache, key: (String, u32, String), summary: String) {
+    let mut cache = cache.lock().unwrap();
+    cache.retain(|(repo, number, _), _| (repo, *number) != (&key.0, key.1));
+    cache.insert(key, summary);
+}
+
+/// Suspend the TUI, attach to a tmux session, and restore the TUI once the
+/// user detaches
+fn attach_session(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    tmux: &str,
+    session: &str,
+) -> Result<()> {
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+    terminal.show_cursor()?;
+
+    // Unset TMUX so attaching works when ampwatch itself runs inside tmux
+    let _ = Command::new(tmux)
+        .args(["attach", "-t", session])
+        .env_remove("TMUX")
+        .status();
+
+    enable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        EnterAlternateScreen,
+        EnableMouseCapture
+    )?;
+    terminal.clear()?;
+    Ok(())
+}
+
+/// A background job's hold on the modal it was started for. Writes are
+/// dropped once that modal has been closed or replaced, so a slow job can't
+/// overwrite whatever is shown next.
+#[derive(Clone)]
+struct ModalJob {
+    content: Arc<Mutex<String>>,
+    loading: Arc<Mutex<bool>>,
+    generation: Arc<AtomicUsize>,
+    started: usize,
+}
+
+impl ModalJob {
+    /// The modal's content, if it still belongs to this job. `open_modal`
+    /// and `close_modal` bump the generation under the same lock.
+    fn content(&self) -> Option<MutexGuard<'_, String>> {
+        let content = self.content.lock().unwrap();
+        (self.generation.load(Ordering::Relaxed) == self.started).then_some(content)
+    }
+
+    fn push_str(&self, s: &str) {
+        if let Some(mut content) = self.content() {
+            content.push_str(s);
         }
     }
 
-    fn prev_instance(&mut self) {
-        if !self.instances.is_empty() {
-            self.selected_instance = if self.selected_instance == 0 {
-                self.instances.len() - 1
-            } else {
-                self.selected_instance - 1
-            };
-            self.instance_list_state.select(Some(self.selected_instance));
-            self.pr_list_state.select(Some(0));
+    fn set(&self, s: String) {
+        if let Some(mut content) = self.content() {
+            *content = s;
         }
     }
 
-    fn next_item(&mut self) {
-        let len = match self.selected_tab {
-            0 => self.current_instance().map(|i| i.agents.len()).unwrap_or(0),
-            1 => self.current_instance().map(|i| i.open_prs.len()).unwrap_or(0),
-            2 => self.current_instance().map(|i| i.closed_prs.len()).unwrap_or(0),
-            _ => 0,
-        };
-        if len > 0 {
-            let state = if self.selected_tab == 0 {
-                &mut self.agent_list_state
-            } else {
-                &mut self.pr_list_state
-            };
-            let i = state.selected().unwrap_or(0);
-            state.select(Some((i + 1) % len));
+    fn is_current(&self) -> bool {
+        self.content().is_some()
+    }
+
+    /// Mark the modal as done loading
+    fn finish(&self) {
+        if let Some(_content) = self.content() {
+            *self.loading.lock().unwrap() = false;
         }
     }
+}
 
-    fn prev_item(&mut self) {
-        let len = match self.selected_tab {
-            0 => self.current_instance().map(|i| i.agents.len()).unwrap_or(0),
-            1 => self.current_instance().map(|i| i.open_prs.len()).unwrap_or(0),
-            2 => self.current_instance().map(|i| i.closed_prs.len()).unwrap_or(0),
-            _ => 0,
-        };
-        if len > 0 {
-            let state = if self.selected_tab == 0 {
-                &mut self.agent_list_state
-            } else {
-                &mut self.pr_list_state
-            };
-            let i = state.selected().unwrap_or(0);
-            state.select(Some(if i == 0 { len - 1 } else { i - 1 }));
+/// Run `cmd`, appending stdout and stderr lines to the modal as they
+/// arrive. The child is parked in `process` so closing the modal can kill
+/// it, in which case `None` is returned.
+fn stream_output(
+    cmd: &mut Command,
+    job: &ModalJob,
+    process: &Arc<Mutex<Option<Child>>>,
+) -> io::Result<Option<ExitStatus>> {
+    let mut child = cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    *process.lock().unwrap() = Some(child);
+
+    let append = |job: &ModalJob, reader: Box<dyn io::Read + Send>| {
+        for line in BufReader::new(reader).lines().map_while(Result::ok) {
+            job.push_str(&format!("{}\n", line));
+        }
+    };
+
+    let stderr_thread = {
+        let job = job.clone();
+        stderr.map(|err| thread::spawn(move || append(&job, Box::new(err))))
+    };
+    if let Some(out) = stdout {
+        append(job, Box::new(out));
+    }
+    if let Some(handle) = stderr_thread {
+        let _ = handle.join();
+    }
+    let child = process.lock().unwrap().take();
+    child.map(|mut child| child.wait()).transpose()
+}
+
+/// `shown` alone, or `shown/total` when GitHub has more than were fetched
+fn shown_of_total(shown: usize, total: Option<u32>) -> String {
+    match total {
+        Some(total) if total as usize > shown => format!("{}/{}", shown, total),
+        _ => shown.to_string(),
+    }
+}
+
+/// `s` cut to at most `max` display columns, ending in `…` if shortened.
+/// Wide characters such as CJK and emoji take two columns.
+fn truncate(s: &str, max: usize) -> String {
+    if s.width() <= max {
+        return s.to_string();
+    }
+    if max == 0 {
+        return String::new();
+    }
+    let mut cut = String::new();
+    let mut used = 0;
+    for c in s.chars() {
+        let w = c.width().unwrap_or(0);
+        // Leave a column for the ellipsis
+        if used + w >= max {
+            break;
         }
+        used += w;
+        cut.push(c);
+    }
+    cut.push('…');
+    cut
+}
+
+/// Duration in its two largest units: `45s`, `7m`, `2h13m`, `3d4h`
+fn format_uptime(secs: u64) -> String {
+    match secs {
+        0..3600 => format_duration(secs),
+        3600..86400 => format!("{}h{}m", secs / 3600, secs % 3600 / 60),
+        _ => format!("{}d{}h", secs / 86400, secs % 86400 / 3600),
+    }
+}
+
+/// Compact duration in its largest whole unit: `45s`, `7m`, `3h`, `2d`
+fn format_duration(secs: u64) -> String {
+    match secs {
+        0..60 => format!("{}s", secs),
+        60..3600 => format!("{}m", secs / 60),
+        3600..86400 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
+}
+
+/// Iteration count, time since the last log write, and iteration rate,
+/// leaving out whatever isn't known
+fn iteration_details(agent: &Agent) -> String {
+    let mut details = format!(" (iter: {}", agent.iterations);
+    if let Some(quiet) = agent.last_log_mtime.and_then(|t| t.elapsed().ok()) {
+        details.push_str(&format!(", {} ago", format_duration(quiet.as_secs())));
+    }
+    if let Some(rate) = agent.iterations_per_hour() {
+        details.push_str(&format!(", {:.1}/h", rate));
+    }
+    details.push(')');
+    details
+}
+
+/// Relative age such as `3h ago`
+fn format_age(age: TimeDelta) -> String {
+    format!("{} ago", format_duration(age.num_seconds().max(0) as u64))
+}
+
+/// `index` clamped into a list of `len` items
+fn nearest_index(index: Option<usize>, len: usize) -> Option<usize> {
+    index.map(|i| i.min(len.saturating_sub(1)))
+}
+
+/// Index after `i` in a non-empty list of `len`, wrapping to the start if `wrap`
+fn step_forward(i: usize, len: usize, wrap: bool) -> usize {
+    if i + 1 < len {
+        i + 1
+    } else if wrap {
+        0
+    } else {
+        len - 1
+    }
+}
+
+/// Index `delta` away from `i` in a list of `len`, clamped to its ends;
+/// `None` if the list is empty
+fn step_by(i: usize, len: usize, delta: isize) -> Option<usize> {
+    let last = len.checked_sub(1)?;
+    Some(i.saturating_add_signed(delta).min(last))
+}
+
+/// Index before `i` in a non-empty list of `len`, wrapping to the end if `wrap`
+fn step_back(i: usize, len: usize, wrap: bool) -> usize {
+    if i > 0 {
+        i - 1
+    } else if wrap {
+        len - 1
+    } else {
+        0
     }
 }
 
 fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let mut config = Config::load();
+    if cli.doctor {
+        doctor::run(&config);
+        return Ok(());
+    }
+
+    if let Some(secs) = cli.refresh_secs {
+        config.refresh_secs = secs;
+    }
+
+    if cli.once || matches!(cli.command, Some(cli::Command::Status)) {
+        status::run(&config);
+        return Ok(());
+    }
+
+    let web_snapshot = match cli.web {
+        Some(port) => {
+            let snapshot = web::Snapshot::default();
+            web::serve(cli.web_bind, port, Arc::clone(&snapshot))?;
+            Some(snapshot)
+        }
+        None => None,
+    };
+
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut app = App::new();
+    let mut app = App::new(config);
+    app.web_snapshot = web_snapshot;
+    app.focus_instance = cli.instance;
+    // Draw the loading screen right away instead of blocking on gh
     app.refresh();
 
-    let tick_rate = Duration::from_millis(200);
-    let refresh_rate = Duration::from_secs(5);
+    let tick_rate = app.config.tick_interval();
+    let refresh_rate = app.config.refresh_interval();
     let mut last_tick = Instant::now();
-    let mut last_refresh = Instant::now();
 
     loop {
         terminal.draw(|f| ui(f, &mut app))?;
@@ -256,30 +2322,165 @@ fn main() -> Result<()> {
         let timeout = tick_rate.saturating_sub(last_tick.elapsed());
 
         if event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
+            let event = event::read()?;
+            if let Event::Mouse(mouse) = event {
+                app.mouse(mouse);
+            }
+            if let Event::Key(key) = event {
                 if key.kind == KeyEventKind::Press {
-                    if app.show_modal {
+                    if app.text_input.is_some() {
+                        app.input_key(key.code);
+                    } else if app.searching {
+                        app.search_key(key.code);
+                    } else if app.show_help {
                         match key.code {
-                            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => {
+                            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('?') => {
+                                app.show_help = false;
+                            }
+                            KeyCode::Down | KeyCode::Char('j') => app.scroll_help(1),
+                            KeyCode::Up | KeyCode::Char('k') => app.scroll_help(-1),
+                            KeyCode::PageDown => app.scroll_help(10),
+                            KeyCode::PageUp => app.scroll_help(-10),
+                            KeyCode::Home | KeyCode::Char('g') => app.help_scroll = 0,
+                            KeyCode::End | KeyCode::Char('G') => app.help_scroll = u16::MAX,
+                            _ => {}
+                        }
+                    } else if app.show_checklist {
+                        let len = app.config.checklist.len();
+                        match key.code {
+                            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('x') => {
+                                app.show_checklist = false;
+                            }
+                            KeyCode::Down | KeyCode::Char('j') => {
+                                app.checklist_cursor = (app.checklist_cursor + 1).min(len - 1);
+                            }
+                            KeyCode::Up | KeyCode::Char('k') => {
+                                app.checklist_cursor = app.checklist_cursor.saturating_sub(1);
+                            }
+                            KeyCode::Char(' ') | KeyCode::Enter => {
+                                app.toggle_checklist_item(app.checklist_cursor);
+                            }
+                            KeyCode::Char(c @ '1'..='9') => {
+                                app.toggle_checklist_item(c as usize - '1' as usize);
+                            }
+                            _ => {}
+                        }
+                    } else if let Some(action) =
+                        app.pending_action.as_ref().filter(|_| app.show_modal)
+                    {
+                        let quitting = matches!(action, PendingAction::Quit);
+                        match key.code {
+                            KeyCode::Char('y') | KeyCode::Char('q') if quitting => break,
+                            KeyCode::Char(c) if c == action.confirm_key() => {
+                                app.run_pending_action()
+                            }
+                            KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('q') => {
+                                app.pending_action = None;
                                 app.show_modal = false;
                             }
                             _ => {}
                         }
+                    } else if app.show_modal {
+                        match key.code {
+                            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => {
+                                app.close_modal();
+                            }
+                            KeyCode::Char('y') => app.copy_modal_text(),
+                            KeyCode::Char('Y') => app.copy_summary_quote(),
+                            KeyCode::Char('w') => app.save_summary(),
+                            KeyCode::Down | KeyCode::Char('j') => app.scroll_modal(1),
+                            KeyCode::Up | KeyCode::Char('k') => app.scroll_modal(-1),
+                            KeyCode::PageDown => app.scroll_modal(10),
+                            KeyCode::PageUp => app.scroll_modal(-10),
+                            KeyCode::Char('S')
+                                if app.summary_pr.is_some()
+                                    && !*app.modal_loading.lock().unwrap() =>
+                            {
+                                app.summarize_pr(true)
+                            }
+                            _ => {}
+                        }
+                    } else if app.show_overview {
+                        match key.code {
+                            KeyCode::Char('q') if app.confirm_quit() => break,
+                            KeyCode::Char('?') => app.open_help(),
+                            KeyCode::Esc | KeyCode::Char('0') => app.show_overview = false,
+                            KeyCode::Down | KeyCode::Char('j') => app.next_item(),
+                            KeyCode::Up | KeyCode::Char('k') => app.prev_item(),
+                            KeyCode::Home | KeyCode::Char('g') => app.scroll_list(isize::MIN),
+                            KeyCode::End | KeyCode::Char('G') => app.scroll_list(isize::MAX),
+                            KeyCode::PageDown => app.scroll_list(app.page_size()),
+                            KeyCode::PageUp => app.scroll_list(-app.page_size()),
+                            KeyCode::Enter => app.open_overview_instance(),
+                            KeyCode::Char('r') => app.refresh(),
+                            KeyCode::Char('p') => app.paused = !app.paused,
+                            _ => {}
+                        }
                     } else {
                         match key.code {
-                            KeyCode::Char('q') => break,
+                            KeyCode::Char('q') if app.confirm_quit() => break,
+                            KeyCode::Char('0') => app.open_overview(),
+                            KeyCode::Char('?') => app.open_help(),
                             KeyCode::Tab => app.next_tab(),
                             KeyCode::BackTab => app.prev_tab(),
                             KeyCode::Down | KeyCode::Char('j') => app.next_item(),
                             KeyCode::Up | KeyCode::Char('k') => app.prev_item(),
+                            KeyCode::Home | KeyCode::Char('g') => app.scroll_list(isize::MIN),
+                            KeyCode::End | KeyCode::Char('G') => app.scroll_list(isize::MAX),
+                            KeyCode::PageDown => app.scroll_list(app.page_size()),
+                            KeyCode::PageUp => app.scroll_list(-app.page_size()),
                             KeyCode::Right | KeyCode::Char('l') => app.next_instance(),
                             KeyCode::Left | KeyCode::Char('h') => app.prev_instance(),
+                            KeyCode::Enter if app.selected_tab > 0 => app.summarize_pr(false),
+                            KeyCode::Char('S') if app.selected_tab > 0 => app.summarize_pr(true),
                             KeyCode::Enter => {
-                                if app.selected_tab > 0 {
-                                    app.summarize_pr();
+                                if let Some(session) = app.attachable_session() {
+                                    attach_session(&mut terminal, &app.config.tmux_bin, &session)?;
                                 }
                             }
                             KeyCode::Char('r') => app.refresh(),
+                            KeyCode::Char('p') => app.paused = !app.paused,
+                            KeyCode::Char('/') if app.selected_tab > 0 => app.searching = true,
+                            KeyCode::Char('a') if app.selected_tab > 0 => app.cycle_author_filter(),
+                            KeyCode::Char('s') if app.selected_tab > 0 => app.cycle_sort_mode(),
+                            KeyCode::Char('t') => app.toggle_theme(),
+                            KeyCode::Char('d') if app.selected_tab > 0 => app.toggle_drafts(),
+                            KeyCode::Char('y') if app.selected_tab > 0 => app.copy_pr_url(),
+                            KeyCode::Char('A') if app.selected_tab == 1 => {
+                                app.toggle_group_by_author()
+                            }
+                            KeyCode::Esc if !app.search_query.is_empty() => {
+                                app.search_query.clear();
+                                app.pr_list_state.select(Some(0));
+                            }
+                            KeyCode::Char(c @ '1'..='9') => {
+                                let index = c as usize - '1' as usize;
+                                if index < app.visible_instances().len() {
+                                    app.select_instance(index);
+                                }
+                            }
+                            KeyCode::Char('F') => app.toggle_open_pr_filter(),
+                            KeyCode::Char('D') => app.show_gh_log(),
+                            KeyCode::Char('*') => app.toggle_pin(),
+                            KeyCode::Char('x') => app.open_checklist(),
+                            KeyCode::Char('O') => app.open_logs_dir(),
+                            KeyCode::Char('e') => app.export_instance(),
+                            KeyCode::Char('N') => app.summarize_newest_pr(),
+                            KeyCode::Char('B') => app.summarize_all_prs(),
+                            KeyCode::Char('T') => app.recommend_review_order(),
+                            KeyCode::Char('o') if app.selected_tab > 0 => app.open_pr_in_browser(),
+                            KeyCode::Char('c') if app.selected_tab > 0 => app.confirm_checkout(),
+                            KeyCode::Char('m') if app.selected_tab == 1 => app.confirm_merge(),
+                            KeyCode::Char('v') if app.selected_tab == 1 => app.prompt_approve(),
+                            KeyCode::Char('M') if app.selected_tab >= 2 => {
+                                app.load_more_closed_prs()
+                            }
+                            KeyCode::Char('R') if app.selected_tab == 0 => app.restart_agent(),
+                            KeyCode::Char('L') if app.selected_tab == 0 => app.view_agent_log(),
+                            KeyCode::Char('i') if app.selected_tab == 0 => app.prompt_send_keys(),
+                            KeyCode::Char('H') if app.selected_tab == 0 => {
+                                app.toggle_hide_stopped_agents()
+                            }
                             _ => {}
                         }
                     }
@@ -292,12 +2493,23 @@ fn main() -> Result<()> {
             last_tick = Instant::now();
         }
 
-        if last_refresh.elapsed() >= refresh_rate {
+        app.poll_refresh();
+        app.poll_pane_preview();
+        app.refresh_pane_preview();
+
+        // Measured from the last completed refresh so slow gh calls don't pile up
+        if !app.paused && app.last_refresh.elapsed() >= refresh_rate {
             app.refresh();
-            last_refresh = Instant::now();
         }
     }
 
+    app.close_modal();
+    // Quitting before the first refresh leaves the saved state alone
+    if app.restore_ui.is_none() {
+        app.state.ui = app.ui_state();
+    }
+    app.state.save();
+
     disable_raw_mode()?;
     execute!(
         terminal.backend_mut(),
@@ -310,6 +2522,28 @@ fn main() -> Result<()> {
 }
 
 fn ui(f: &mut Frame, app: &mut App) {
+    let theme = app.theme.clone();
+    let area = f.area();
+    if area.width < MIN_WIDTH || area.height < MIN_HEIGHT {
+        // Nothing below is clickable until the layout fits again
+        app.click_targets.clear();
+        let message = format!(
+            "Terminal too small ({}x{}); need at least {}x{}",
+            area.width, area.height, MIN_WIDTH, MIN_HEIGHT
+        );
+        let paragraph = Paragraph::new(message)
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(theme.stuck))
+            .wrap(Wrap { trim: true });
+        let middle = Rect {
+            y: area.y + area.height / 2,
+            height: area.height - area.height / 2,
+            ..area
+        };
+        f.render_widget(paragraph, middle);
+        return;
+    }
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -320,37 +2554,75 @@ fn ui(f: &mut Frame, app: &mut App) {
         ])
         .split(f.area());
 
-    // Animated spinner frames
-    const SPINNER_FRAMES: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
-    let spinner = SPINNER_FRAMES[app.tick % SPINNER_FRAMES.len()];
+    let spinner = spinner_frame(app.tick);
 
     // Get current instance info for header
-    let (open_count, merged_count) = app
+    let (open_count, merged_count, closed_count) = app
         .current_instance()
-        .map(|i| (i.open_prs.len(), i.closed_prs.len()))
-        .unwrap_or((0, 0));
+        .map(|i| {
+            (
+                i.open_prs.len().to_string(),
+                shown_of_total(i.merged_prs.len(), i.merged_total),
+                shown_of_total(i.closed_prs.len(), i.closed_total),
+            )
+        })
+        .unwrap_or_default();
 
-    // Header
-    let header = Paragraph::new(vec![Line::from(vec![
-        Span::styled(format!(" {} ", spinner), Style::default().fg(Color::Green)),
+    // Agents summary: running / completed / crashed
+    let count_status = |status: AgentStatus| {
+        app.current_instance()
+            .map(|i| i.agents.iter().filter(|a| a.status() == status).count())
+            .unwrap_or(0)
+    };
+    let agents_summary = [
+        Span::raw(" │ agents "),
         Span::styled(
-            "AMPWATCH ",
-            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            format!("{}▶ ", count_status(AgentStatus::Running)),
+            Style::default().fg(theme.running),
+        ),
+        Span::styled(
+            format!("{}✓ ", count_status(AgentStatus::Completed)),
+            Style::default().fg(theme.accent),
+        ),
+        Span::styled(
+            format!("{}✗", count_status(AgentStatus::Crashed)),
+            Style::default().fg(theme.stopped),
         ),
+    ];
+
+    // Header
+    let mut header_spans = vec![
+        Span::styled(format!(" {} ", spinner), Style::default().fg(theme.live)),
         Span::styled(
-            "LIVE",
+            "AMPWATCH ",
             Style::default()
-                .fg(Color::Green)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD),
         ),
+        if app.paused {
+            Span::styled(
+                "PAUSED",
+                Style::default()
+                    .fg(theme.highlight)
+                    .add_modifier(Modifier::BOLD),
+            )
+        } else {
+            Span::styled(
+                "LIVE",
+                Style::default().fg(theme.live).add_modifier(Modifier::BOLD),
+            )
+        },
+        Span::styled(
+            if app.refreshing() { " ⟳" } else { "  " },
+            Style::default().fg(theme.muted),
+        ),
         Span::raw(" │ "),
+    ];
+    let tab_spans = [
         Span::styled(
-            format!(
-                " Agents {} ",
-                if app.selected_tab == 0 { "●" } else { "○" }
-            ),
+            format!(" Agents {} ", if app.selected_tab == 0 { "●" } else { "○" }),
             if app.selected_tab == 0 {
-                Style::default().fg(Color::Yellow)
+                Style::default().fg(theme.highlight)
             } else {
                 Style::default()
             },
@@ -362,7 +2634,7 @@ fn ui(f: &mut Frame, app: &mut App) {
                 if app.selected_tab == 1 { "●" } else { "○" }
             ),
             if app.selected_tab == 1 {
-                Style::default().fg(Color::Green)
+                Style::default().fg(theme.pr_open)
             } else {
                 Style::default()
             },
@@ -374,198 +2646,833 @@ fn ui(f: &mut Frame, app: &mut App) {
                 if app.selected_tab == 2 { "●" } else { "○" }
             ),
             if app.selected_tab == 2 {
-                Style::default().fg(Color::Magenta)
+                Style::default().fg(theme.pr_merged)
             } else {
                 Style::default()
             },
         ),
-    ])])
-    .block(Block::default().borders(Borders::ALL));
+        Span::styled(
+            format!(
+                " Closed PRs ({}) {} ",
+                closed_count,
+                if app.selected_tab == 3 { "●" } else { "○" }
+            ),
+            if app.selected_tab == 3 {
+                Style::default().fg(theme.pr_closed)
+            } else {
+                Style::default()
+            },
+        ),
+    ];
+    // Remember where each tab lands so it can be clicked
+    app.click_targets.clear();
+    let mut x = chunks[0].x + 1 + header_spans.iter().map(|s| s.width() as u16).sum::<u16>();
+    for (tab, span) in tab_spans.iter().enumerate() {
+        let width = span.width() as u16;
+        let area = Rect::new(x, chunks[0].y + 1, width, 1).intersection(chunks[0]);
+        app.click_targets.push((area, ClickTarget::Tab(tab)));
+        x += width;
+    }
+    header_spans.extend(tab_spans);
+    header_spans.extend(agents_summary);
+    let used: usize = header_spans.iter().map(Span::width).sum();
+    let header =
+        Paragraph::new(Line::from(header_spans)).block(Block::default().borders(Borders::ALL));
     f.render_widget(header, chunks[0]);
 
+    // Right-aligned refresh age, amber once refreshes seem stalled
+    if let Some(refreshed) = app.last_success {
+        let age = refreshed.elapsed();
+        let updated = format!("updated {} ago ", format_duration(age.as_secs()));
+        // Paused views are expected to go stale
+        let stalled = !app.paused && age > app.config.refresh_interval() * 3;
+        let color = if stalled { theme.stuck } else { theme.muted };
+        let inner = Block::default().borders(Borders::ALL).inner(chunks[0]);
+        if used + updated.len() < inner.width as usize {
+            let updated = Paragraph::new(updated)
+                .style(Style::default().fg(color))
+                .alignment(Alignment::Right);
+            f.render_widget(updated, inner);
+        }
+    }
+
     // Instance selector
-    render_instance_selector(f, app, chunks[1]);
+    let targets = render_instance_selector(f, app, chunks[1]);
+    app.click_targets.extend(targets);
 
     // Content
-    if app.instances.is_empty() {
-        let empty = Paragraph::new("No amptown instances found. Start one with: amptown <repo-path>")
-            .style(Style::default().fg(Color::DarkGray))
-            .block(Block::default().borders(Borders::ALL).title(" No Instances "));
+    if app.loading {
+        let loading = Paragraph::new(format!("{} Discovering instances…", spinner))
+            .style(Style::default().fg(theme.muted))
+            .block(Block::default().borders(Borders::ALL).title(" Loading "));
+        f.render_widget(loading, chunks[2]);
+    } else if app.instances.is_empty() {
+        let mut lines = vec![Line::from(
+            "No amptown instances found. Start one with: amptown <repo-path>",
+        )];
+        for warning in &app.env_warnings {
+            lines.push(Line::styled(
+                format!("✗ {}", warning),
+                Style::default()
+                    .fg(theme.stopped)
+                    .add_modifier(Modifier::BOLD),
+            ));
+        }
+        for warning in &app.warnings {
+            lines.push(Line::styled(
+                format!("⚠ {}", warning),
+                Style::default().fg(theme.highlight),
+            ));
+        }
+        let empty = Paragraph::new(lines)
+            .style(Style::default().fg(theme.muted))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(" No Instances "),
+            );
         f.render_widget(empty, chunks[2]);
-    } else if let Some(instance) = app.instances.get(app.selected_instance) {
-        match app.selected_tab {
-            0 => render_agents(f, instance, &mut app.agent_list_state, chunks[2]),
-            1 => {
-                let prs = instance.open_prs.clone();
-                render_prs(f, &prs, &mut app.pr_list_state, chunks[2], "Open Pull Requests");
-            }
-            2 => {
-                let prs = instance.closed_prs.clone();
-                render_prs(
-                    f,
-                    &prs,
-                    &mut app.pr_list_state,
-                    chunks[2],
-                    "Merged Pull Requests",
-                );
-            }
-            _ => {}
+    } else if app.show_overview {
+        let mut list_state = app.overview_list_state.clone();
+        render_overview(f, app, &mut list_state, chunks[2]);
+        let rows = Block::default().borders(Borders::ALL).inner(chunks[2]);
+        let offset = list_state.offset();
+        app.click_targets
+            .push((rows, ClickTarget::OverviewList { offset }));
+        app.overview_list_state = list_state;
+    } else if app.current_instance().is_some() {
+        let title = app.pr_list_title();
+        if app.selected_tab == 0 {
+            let targets = render_agents(f, app, chunks[2]);
+            app.click_targets.extend(targets);
+        } else {
+            let mut list_state = app.pr_list_state.clone();
+            let (rows, offset) = render_prs(f, app, &mut list_state, chunks[2], &title);
+            let area = Block::default().borders(Borders::ALL).inner(chunks[2]);
+            app.click_targets
+                .push((area, ClickTarget::PrList { rows, offset }));
+            app.pr_list_state = list_state;
         }
+    } else {
+        let empty = Paragraph::new("No instances with open PRs. Press F to show all instances.")
+            .style(Style::default().fg(theme.muted))
+            .block(Block::default().borders(Borders::ALL).title(" Filtered "));
+        f.render_widget(empty, chunks[2]);
     }
 
     // Footer
-    let footer_text = if app.instances.len() > 1 {
-        "q: Quit │ Tab: View │ ←→: Instance │ ↑↓: Navigate │ Enter: Summarize │ r: Refresh"
-    } else if app.selected_tab == 0 {
-        "q: Quit │ Tab: Switch view │ ↑↓: Navigate │ r: Refresh"
-    } else {
-        "q: Quit │ Tab: Switch view │ ↑↓: Navigate │ Enter: Summarize PR │ r: Refresh"
-    };
-    let footer = Paragraph::new(footer_text)
-        .style(Style::default().fg(Color::DarkGray))
-        .block(Block::default().borders(Borders::ALL));
+    let footer_text = fit_hints(&footer_hints(app), chunks[3].width.saturating_sub(2));
+    let footer = match &app.toast {
+        Some((message, shown)) if shown.elapsed() < TOAST_DURATION => {
+            Paragraph::new(message.as_str()).style(Style::default().fg(theme.highlight))
+        }
+        _ => Paragraph::new(footer_text).style(Style::default().fg(theme.muted)),
+    }
+    .block(Block::default().borders(Borders::ALL));
     f.render_widget(footer, chunks[3]);
 
     // Modal
     if app.show_modal {
         render_modal(f, app);
     }
+    if app.show_checklist {
+        render_checklist(f, app);
+    }
+    if app.show_help {
+        render_help(f, app);
+    }
+    if let Some(input) = &app.text_input {
+        render_text_input(f, app, input);
+    }
+}
+
+/// Every key binding, shown by the `?` overlay
+const HELP: &[(&str, &str)] = &[
+    ("q", "Quit"),
+    ("?", "Toggle this help"),
+    ("Tab / Shift-Tab", "Next / previous view"),
+    ("←→ / h l", "Previous / next instance"),
+    ("1-9", "Jump to instance"),
+    ("0", "Overview of all instances"),
+    ("↑↓ / j k", "Navigate"),
+    ("Home End / g G", "First / last item"),
+    ("PgUp PgDn", "Move by a page"),
+    ("Enter", "Summarize the selected PR / attach to the agent"),
+    ("/", "Search PRs by title or branch (Esc clears)"),
+    ("a", "Cycle the PR author filter"),
+    ("s", "Cycle PR sort: number, age, state"),
+    ("d", "Hide / show draft PRs"),
+    ("A", "Group open PRs by author"),
+    ("o", "Open the selected PR in a browser"),
+    ("c", "Check out the selected PR's branch"),
+    ("m", "Merge the selected open PR"),
+    ("v", "Approve the selected open PR, optionally commenting"),
+    ("M", "Load more merged and closed PRs"),
+    ("S", "Re-summarize, ignoring the cache"),
+    ("N", "Summarize the newest open PR"),
+    ("B", "Summarize every open PR in turn"),
+    ("T", "Ask amp which open PR to review first"),
+    ("t", "Switch between dark and light theme"),
+    ("r", "Refresh now"),
+    ("p", "Pause / resume auto-refresh"),
+    ("F", "Only instances with open PRs"),
+    ("H", "Hide stopped agents (Agents view)"),
+    ("R", "Restart the selected agent (Agents view)"),
+    ("L", "View the selected agent's log (Agents view)"),
+    (
+        "i",
+        "Type a line into the selected running agent (Agents view)",
+    ),
+    ("*", "Pin the selected PR"),
+    ("x", "Review checklist for the selected PR"),
+    ("D", "gh command log"),
+    ("O", "Open the logs directory"),
+    ("e", "Export the instance as JSON to ~"),
+    ("y", "Copy the selected PR's URL (in a modal: its text)"),
+    ("Y", "Copy summary as Markdown (in summary)"),
+    ("w", "Save summary to ~/ampwatch/summaries (in summary)"),
+];
+
+/// Footer hints for the keys that do something right now, checked in the
+/// same order as the key handler in `main`. In the main view quit and help
+/// come first so they survive truncation.
+fn footer_hints(app: &App) -> Vec<(&'static str, &'static str)> {
+    if app.text_input.is_some() {
+        return vec![("Enter", "Send"), ("Esc", "Cancel")];
+    }
+    if app.searching {
+        return vec![
+            ("Enter", "Apply"),
+            ("Esc", "Clear"),
+            ("Type", "Filter by title or branch"),
+        ];
+    }
+    if app.show_help {
+        return vec![("↑↓", "Scroll"), ("Esc", "Close help")];
+    }
+    if app.show_checklist {
+        return vec![
+            ("Space", "Toggle"),
+            ("1-9", "Toggle item"),
+            ("Esc", "Close"),
+        ];
+    }
+    if let Some(action) = app.pending_action.as_ref().filter(|_| app.show_modal) {
+        let confirm = if action.confirm_key() == 'Y' {
+            "Y"
+        } else {
+            "y"
+        };
+        return vec![(confirm, "Confirm"), ("n", "Cancel")];
+    }
+    if app.show_modal {
+        let mut hints = vec![("Esc", "Close"), ("↑↓", "Scroll"), ("y", "Copy")];
+        if app.summary_pr.is_some() {
+            hints.extend([("Y", "Copy quote"), ("w", "Save")]);
+            if !*app.modal_loading.lock().unwrap() {
+                hints.push(("S", "Re-summarize"));
+            }
+        }
+        return hints;
+    }
+    if app.show_overview {
+        return vec![
+            ("q", "Quit"),
+            ("?", "Help"),
+            ("↑↓", "Navigate"),
+            ("Enter", "Open instance"),
+            ("Esc", "Close overview"),
+        ];
+    }
+
+    let mut hints = vec![("q", "Quit"), ("?", "Help"), ("Tab", "View")];
+    if app.instances.len() > 1 {
+        hints.push(("←→", "Instance"));
+        hints.push(("1-9", "Jump"));
+    }
+    hints.push(("↑↓", "Navigate"));
+    if app.selected_tab == 0 {
+        if let Some(agent) = app.selected_agent() {
+            if agent.is_running {
+                hints.extend([("Enter", "Attach"), ("i", "Type")]);
+            }
+            hints.extend([("L", "Log"), ("R", "Restart")]);
+        }
+    } else {
+        if let Some(pr) = app.selected_pr() {
+            hints.extend([("Enter", "Summarize"), ("o", "Open"), ("c", "Checkout")]);
+            if pr.state == "OPEN" {
+                hints.extend([("m", "Merge"), ("v", "Approve")]);
+            }
+        }
+        if !app.search_query.is_empty() {
+            hints.push(("Esc", "Clear search"));
+        } else {
+            hints.push(("/", "Search"));
+        }
+        if app.selected_tab >= 2 {
+            hints.push(("M", "More"));
+        }
+    }
+    hints.push(("r", "Refresh"));
+    if app.instances.len() > 1 {
+        hints.push(("F", "PR filter"));
+    }
+    hints
+}
+
+/// Render hints as `q: Quit │ ?: Help │ …` if they fit in `width`, else as
+/// compact `q:Quit ?:Help …`, dropping trailing hints that don't fit
+fn fit_hints(hints: &[(&str, &str)], width: u16) -> String {
+    let width = width as usize;
+    let full = hints
+        .iter()
+        .map(|(key, label)| format!("{}: {}", key, label))
+        .collect::<Vec<_>>()
+        .join(" │ ");
+    if full.chars().count() <= width {
+        return full;
+    }
+
+    let mut compact = String::new();
+    for (i, (key, label)) in hints.iter().enumerate() {
+        let hint = format!("{}:{}", key, label);
+        let extra = usize::from(!compact.is_empty()) + hint.chars().count();
+        // Always keep quit and help, even if the terminal clips them
+        if i >= 2 && compact.chars().count() + extra > width {
+            break;
+        }
+        if !compact.is_empty() {
+            compact.push(' ');
+        }
+        compact.push_str(&hint);
+    }
+    compact
+}
+
+fn render_help(f: &mut Frame, app: &mut App) {
+    let theme = &app.theme;
+    let lines: Vec<Line> = HELP
+        .iter()
+        .map(|(key, action)| {
+            Line::from(vec![
+                Span::styled(
+                    format!("{:>16}  ", key),
+                    Style::default().fg(theme.highlight),
+                ),
+                Span::raw(*action),
+            ])
+        })
+        .collect();
+    let help = Paragraph::new(lines).block(
+        Block::default()
+            .title(" Keys │ ↑↓: scroll │ Esc: close ")
+            .borders(Borders::ALL)
+            .style(Style::default().bg(theme.modal_bg)),
+    );
+
+    let area = centered_rect(60, 70, f.area());
+    let lines = help.line_count(area.width.saturating_sub(2)) as u16;
+    app.help_scroll = clamp_scroll(app.help_scroll, lines, area);
+    let help = help.scroll((app.help_scroll, 0));
+    f.render_widget(Clear, area);
+    f.render_widget(help, area);
 }
 
-fn render_instance_selector(f: &mut Frame, app: &App, area: Rect) {
+/// Draw the instance tabs, returning where each one landed
+fn render_instance_selector(f: &mut Frame, app: &App, area: Rect) -> Vec<(Rect, ClickTarget)> {
+    let theme = &app.theme;
     if app.instances.is_empty() {
         let empty = Paragraph::new("No instances running")
-            .style(Style::default().fg(Color::DarkGray))
+            .style(Style::default().fg(theme.muted))
             .block(Block::default().borders(Borders::ALL).title(" Instances "));
         f.render_widget(empty, area);
-        return;
+        return Vec::new();
     }
 
-    let titles: Vec<Line> = app
-        .instances
+    let visible = app.visible_instances();
+    let hidden = app.instances.len() - visible.len();
+    // Each tab's share of the row; Tabs adds a column of padding on both
+    // sides and a divider
+    let tab_width =
+        (area.width.saturating_sub(2) as usize / visible.len().max(1)).saturating_sub(3);
+
+    let titles: Vec<Line> = visible
         .iter()
+        .map(|&idx| &app.instances[idx])
         .enumerate()
         .map(|(i, inst)| {
-            let running = inst.running_agent_count();
             let style = if i == app.selected_instance {
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(theme.highlight)
                     .add_modifier(Modifier::BOLD)
             } else {
                 Style::default()
             };
-            Line::styled(format!(" {} ({}/6) ", inst.repo_name(), running), style)
+            // Keep the spinner's column when idle so labels don't shift
+            let spinner = if inst.refreshing {
+                spinner_frame(app.tick)
+            } else {
+                " "
+            };
+            let mut spans = vec![
+                Span::raw(format!("{} ", spinner)),
+                // Label, filled in below once the rest of the tab's width is known
+                Span::raw(""),
+                Span::raw(format!(" ({}) ", inst.agent_summary())),
+                match inst.uptime() {
+                    Some(uptime) if inst.running_agent_count() > 0 => Span::styled(
+                        format!("up {} ", format_uptime(uptime.as_secs())),
+                        Style::default().fg(theme.muted),
+                    ),
+                    _ => Span::raw(""),
+                },
+                Span::styled(
+                    format!("○{} ", inst.open_prs.len()),
+                    Style::default().fg(theme.pr_open),
+                ),
+                Span::styled(
+                    format!("✓{} ", inst.merged_prs.len()),
+                    Style::default().fg(theme.pr_merged),
+                ),
+            ];
+            if let Some(branch) = &inst.branch {
+                spans.push(Span::styled(
+                    format!("{} ", branch),
+                    Style::default().fg(theme.muted),
+                ));
+            }
+            if inst.dirty {
+                // Uncommitted changes left in the working tree
+                spans.push(Span::styled("✱ ", Style::default().fg(theme.stuck)));
+            }
+            let room = tab_width.saturating_sub(spans.iter().map(Span::width).sum());
+            spans[1] = Span::raw(truncate(
+                &app.config.instance_label(inst),
+                room.clamp(4, MAX_INSTANCE_LABEL),
+            ));
+            Line::from(spans).style(style)
         })
         .collect();
 
-    let tabs = Tabs::new(titles)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title(format!(" Instances ({}) ", app.instances.len())),
+    let title = if hidden > 0 {
+        format!(
+            " Instances ({}, {} without open PRs hidden) ",
+            visible.len(),
+            hidden
         )
+    } else {
+        format!(" Instances ({}) ", app.instances.len())
+    };
+
+    let titles_width: Vec<usize> = titles.iter().map(Line::width).collect();
+    let tabs = Tabs::new(titles)
+        .block(Block::default().borders(Borders::ALL).title(title))
         .select(app.selected_instance)
-        .style(Style::default().fg(Color::White))
+        .style(Style::default().fg(theme.text))
         .highlight_style(
             Style::default()
-                .fg(Color::Yellow)
+                .fg(theme.highlight)
                 .add_modifier(Modifier::BOLD),
         );
 
     f.render_widget(tabs, area);
+
+    // Tabs pads each title with a space on both sides and divides them with │
+    let mut targets = Vec::new();
+    let mut x = area.x + 1;
+    for (i, title) in titles_width.into_iter().enumerate() {
+        let width = title as u16 + 2;
+        let tab = Rect::new(x, area.y + 1, width, 1).intersection(area);
+        targets.push((tab, ClickTarget::Instance(i)));
+        x += width + 1;
+    }
+    targets
 }
 
-fn render_agents(f: &mut Frame, instance: &Instance, _list_state: &mut ListState, area: Rect) {
+/// Draw the agent columns, returning their list areas for mouse clicks
+fn render_agents(f: &mut Frame, app: &App, area: Rect) -> Vec<(Rect, ClickTarget)> {
+    let theme = &app.theme;
+    let mut targets = Vec::new();
+    let Some(instance) = app.current_instance() else {
+        return targets;
+    };
+    let agents = app.displayed_agents();
+    let selected = app.selected_agent();
+
+    // Live pane of the selected running agent on the right
+    let preview = app.pane_preview.as_ref().filter(|(session, _)| {
+        selected.is_some_and(|a| a.is_running && a.session_name() == *session)
+    });
+    let area = match preview {
+        Some((session, capture)) => {
+            let split = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(area);
+            render_pane_preview(f, app, session, capture, split[1]);
+            split[0]
+        }
+        None => area,
+    };
+
+    // Details of the selected agent, running or not, under the columns
+    let area = match selected {
+        Some(agent) => {
+            let split = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(4)])
+                .split(area);
+            render_agent_detail(f, app, agent, split[1]);
+            split[0]
+        }
+        None => area,
+    };
+    let selected = selected.map(|a| a.name.as_str());
+
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
         .split(area);
 
-    // Reviewers
-    let reviewers: Vec<ListItem> = instance
-        .agents
-        .iter()
-        .filter(|a| matches!(a.agent_type, AgentType::Reviewer))
-        .map(|a| {
-            let status_color = if a.is_running {
-                Color::Green
-            } else {
-                Color::Red
-            };
-            let status_icon = if a.is_running { "●" } else { "○" };
-            ListItem::new(Line::from(vec![
-                Span::styled(format!("{} ", status_icon), Style::default().fg(status_color)),
-                Span::styled(&a.name, Style::default().add_modifier(Modifier::BOLD)),
-                Span::raw(format!(" (iter: {})", a.iterations)),
-            ]))
-        })
-        .collect();
+    let columns = [
+        (AgentType::Reviewer, "Reviewers", theme.reviewer),
+        (AgentType::Implementer, "Implementers", theme.implementer),
+    ];
+    for ((agent_type, title, color), chunk) in columns.into_iter().zip(chunks.iter()) {
+        let column: Vec<&Agent> = agents
+            .iter()
+            .copied()
+            .filter(|a| a.agent_type == agent_type)
+            .collect();
+        let hidden = instance
+            .agents
+            .iter()
+            .filter(|a| a.agent_type == agent_type)
+            .count()
+            - column.len();
 
-    let reviewers_list = List::new(reviewers).block(
-        Block::default()
-            .title(" Reviewers ")
-            .borders(Borders::ALL)
-            .style(Style::default().fg(Color::Blue)),
-    );
-    f.render_widget(reviewers_list, chunks[0]);
+        // Indented under the name, within the column's borders
+        let activity_width = (chunk.width as usize).saturating_sub(4);
+        let stuck_threshold = app.config.stuck_threshold();
+        // Green while writing output, yellow when idle, gray once long idle
+        let recency_color = |a: &Agent| match a.last_log_mtime.and_then(|t| t.elapsed().ok()) {
+            Some(quiet) if quiet < Duration::from_secs(60) => theme.running,
+            Some(quiet) if quiet < stuck_threshold => theme.highlight,
+            _ => theme.muted,
+        };
+        let items: Vec<ListItem> = column
+            .iter()
+            .map(|a| {
+                let stuck = a.stuck_for(stuck_threshold);
+                let status_color = if stuck.is_some() {
+                    theme.stuck
+                } else if a.is_running {
+                    theme.running
+                } else {
+                    theme.stopped
+                };
+                let status_icon = if a.is_running { "●" } else { "○" };
+                let mut name_line = vec![
+                    Span::styled(
+                        format!("{} ", status_icon),
+                        Style::default().fg(status_color),
+                    ),
+                    Span::styled(&a.name, Style::default().add_modifier(Modifier::BOLD)),
+                    Span::styled(iteration_details(a), Style::default().fg(recency_color(a))),
+                ];
+                if a.has_errors() {
+                    // Recent error in the log; the line itself is in the detail panel
+                    name_line.insert(2, Span::styled(" ⚠", Style::default().fg(theme.stopped)));
+                }
+                let open_prs = instance.open_pr_count(a, &app.config.pr_branch_pattern);
+                if open_prs > 0 {
+                    name_line.push(Span::styled(
+                        format!(
+                            " {} open PR{}",
+                            open_prs,
+                            if open_prs == 1 { "" } else { "s" }
+                        ),
+                        Style::default().fg(theme.pr_open),
+                    ));
+                }
+                if let Some(quiet) = stuck {
+                    name_line.push(Span::styled(
+                        format!(" quiet {}", format_duration(quiet.as_secs())),
+                        Style::default().fg(theme.stuck),
+                    ));
+                }
+                let activity = if !a.last_activity.is_empty() {
+                    truncate(a.last_activity.trim(), activity_width)
+                } else if a.is_running {
+                    "waiting for output".to_string()
+                } else {
+                    "idle".to_string()
+                };
+                ListItem::new(vec![
+                    Line::from(name_line),
+                    Line::styled(format!("  {}", activity), Style::default().fg(theme.muted)),
+                ])
+            })
+            .collect();
 
-    // Implementers
-    let implementers: Vec<ListItem> = instance
-        .agents
-        .iter()
-        .filter(|a| matches!(a.agent_type, AgentType::Implementer))
-        .map(|a| {
-            let status_color = if a.is_running {
-                Color::Green
-            } else {
-                Color::Red
-            };
-            let status_icon = if a.is_running { "●" } else { "○" };
-            ListItem::new(Line::from(vec![
-                Span::styled(format!("{} ", status_icon), Style::default().fg(status_color)),
-                Span::styled(&a.name, Style::default().add_modifier(Modifier::BOLD)),
-                Span::raw(format!(" (iter: {})", a.iterations)),
-            ]))
-        })
-        .collect();
+        let title = if hidden > 0 {
+            format!(" {} ({} stopped hidden) ", title, hidden)
+        } else {
+            format!(" {} ", title)
+        };
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title(title)
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(color)),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
 
-    let implementers_list = List::new(implementers).block(
-        Block::default()
-            .title(" Implementers ")
-            .borders(Borders::ALL)
-            .style(Style::default().fg(Color::Magenta)),
-    );
-    f.render_widget(implementers_list, chunks[1]);
+        let mut state = ListState::default().with_selected(
+            column
+                .iter()
+                .position(|a| Some(a.name.as_str()) == selected),
+        );
+        f.render_stateful_widget(list, *chunk, &mut state);
+
+        let rows = Block::default().borders(Borders::ALL).inner(*chunk);
+        let names = column.iter().map(|a| a.name.clone()).collect();
+        let offset = state.offset();
+        targets.push((rows, ClickTarget::AgentList { names, offset }));
+    }
+    targets
+}
+
+/// The selected agent's model and latest log error, if it has one
+fn render_agent_detail(f: &mut Frame, app: &App, agent: &Agent, area: Rect) {
+    let width = area.width.saturating_sub(4) as usize;
+    let model = Line::from(vec![
+        Span::styled("model: ", Style::default().fg(app.theme.muted)),
+        Span::raw(agent.model_label()),
+    ]);
+    let error = match &agent.last_error_line {
+        Some(error) => Line::styled(
+            format!("⚠ {}", truncate(error, width.saturating_sub(2))),
+            Style::default().fg(app.theme.stopped),
+        ),
+        None => Line::styled("No recent errors", Style::default().fg(app.theme.muted)),
+    };
+    let block = Block::default()
+        .title(format!(" {} ", agent.name))
+        .borders(Borders::ALL);
+    f.render_widget(Paragraph::new(vec![model, error]).block(block), area);
+}
+
+/// Bottom of a captured tmux pane, or why it couldn't be captured
+fn render_pane_preview(
+    f: &mut Frame,
+    app: &App,
+    session: &str,
+    capture: &Result<Vec<String>, String>,
+    area: Rect,
+) {
+    let block = Block::default()
+        .title(format!(" {} ", session))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app.theme.accent));
+    let paragraph = match capture {
+        Ok(lines) => {
+            let height = area.height.saturating_sub(2) as usize;
+            let skip = lines.len().saturating_sub(height);
+            let text: Vec<Line> = lines[skip..]
+                .iter()
+                .map(|l| Line::raw(l.as_str()))
+                .collect();
+            Paragraph::new(text)
+        }
+        Err(e) => Paragraph::new(format!("✗ {}", e))
+            .style(Style::default().fg(app.theme.stopped))
+            .wrap(Wrap { trim: true }),
+    };
+    f.render_widget(paragraph.block(block), area);
 }
 
+/// Draw the PR list, under author headers when grouped. `list_state` indexes
+/// the displayed PRs, not rows; returns which PR each row shows and the
+/// scroll offset in rows.
 fn render_prs(
     f: &mut Frame,
-    prs: &[PullRequest],
+    app: &App,
     list_state: &mut ListState,
     area: Rect,
     title: &str,
-) {
+) -> (Vec<Option<usize>>, usize) {
+    let theme = &app.theme;
+    let error = app.current_instance().and_then(|i| i.last_error.as_ref());
+    if let Some(error) = error.filter(|_| app.displayed_prs().is_empty()) {
+        let message = Paragraph::new(format!("✗ {}", error))
+            .style(Style::default().fg(theme.stopped))
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::default()
+                    .title(format!(" {} ", title))
+                    .borders(Borders::ALL),
+            );
+        f.render_widget(message, area);
+        return (Vec::new(), 0);
+    }
+    let pinned = app.pinned_prs();
+    let repo = app
+        .current_instance()
+        .map(|i| i.state_key())
+        .unwrap_or_default();
+    let checklist_len = app.config.checklist.len();
+    let instance = app.current_instance();
+    let now = Utc::now();
+    let width = area.width.saturating_sub(2) as usize;
+
+    let prs = app.displayed_prs();
     let items: Vec<ListItem> = prs
         .iter()
-        .map(|pr| {
+        .map(|&pr| {
             let state_color = match pr.state.as_str() {
-                "OPEN" => Color::Green,
-                "MERGED" => Color::Magenta,
-                "CLOSED" => Color::Red,
-                _ => Color::White,
+                "OPEN" => theme.pr_open,
+                "MERGED" => theme.pr_merged,
+                "CLOSED" => theme.pr_closed,
+                _ => theme.pr_unknown,
             };
-            ListItem::new(Line::from(vec![
+            let pin = if pinned.contains(&pr.number) {
+                "⚑ "
+            } else {
+                "  "
+            };
+            let mut spans = vec![
+                Span::raw(pin),
                 Span::styled(
                     format!("#{:<4} ", pr.number),
-                    Style::default().fg(Color::Yellow),
+                    Style::default().fg(theme.pr_number),
                 ),
-                Span::styled(format!("{:<8} ", pr.state), Style::default().fg(state_color)),
-                Span::raw(&pr.title),
-            ]))
+                Span::styled(
+                    format!("{:<8} ", pr.state),
+                    Style::default().fg(state_color),
+                ),
+                match pr.checks {
+                    CheckStatus::Passing => Span::styled("✓ ", Style::default().fg(theme.running)),
+                    CheckStatus::Failing => Span::styled("✗ ", Style::default().fg(theme.stopped)),
+                    CheckStatus::Pending => {
+                        Span::styled("● ", Style::default().fg(theme.highlight))
+                    }
+                    CheckStatus::None => Span::raw("  "),
+                },
+                // Only open PRs are still waiting on reviewers
+                match pr.review_decision.as_str() {
+                    _ if pr.state != "OPEN" => Span::raw("         "),
+                    "APPROVED" => Span::styled("APPROVED ", Style::default().fg(theme.running)),
+                    "CHANGES_REQUESTED" => {
+                        Span::styled("CHANGES  ", Style::default().fg(theme.stopped))
+                    }
+                    _ => Span::styled("REVIEW   ", Style::default().fg(theme.highlight)),
+                },
+                Span::styled(
+                    if pr.is_draft { "[draft] " } else { "" },
+                    Style::default().fg(theme.muted),
+                ),
+            ];
+            let title_at = spans.len();
+            spans.push(Span::raw(pr.title.clone()));
+            if let Some(agent) =
+                instance.and_then(|i| i.pr_agent(pr, &app.config.pr_branch_pattern))
+            {
+                let color = match agent.agent_type {
+                    AgentType::Reviewer => theme.reviewer,
+                    AgentType::Implementer => theme.implementer,
+                };
+                spans.push(Span::styled(
+                    format!("  {}", agent.name),
+                    Style::default().fg(color),
+                ));
+            }
+            let ticked = app.state.checked_items(&repo, pr.number).len();
+            if ticked > 0 {
+                let color = if ticked >= checklist_len {
+                    theme.running
+                } else {
+                    theme.muted
+                };
+                spans.push(Span::styled(
+                    format!("  ☑ {}/{}", ticked, checklist_len),
+                    Style::default().fg(color),
+                ));
+            }
+
+            // Right-align the diff size and age, keeping at least a two-space gap
+            let age = match pr.created() {
+                Some(created) => format_age(now - created),
+                None => pr.created_at.clone(),
+            };
+            let mut right = Vec::new();
+            if pr.comments > 0 {
+                let comments = if app.config.emoji {
+                    format!("💬 {}  ", pr.comments)
+                } else {
+                    format!("c:{}  ", pr.comments)
+                };
+                right.push(Span::styled(comments, Style::default().fg(theme.muted)));
+            }
+            if pr.additions + pr.deletions + pr.changed_files > 0 {
+                right.extend([
+                    Span::styled(
+                        format!("+{}", pr.additions),
+                        Style::default().fg(theme.running),
+                    ),
+                    Span::styled(
+                        format!("/-{}", pr.deletions),
+                        Style::default().fg(theme.stopped),
+                    ),
+                    Span::styled(
+                        format!(" {}f  ", pr.changed_files),
+                        Style::default().fg(theme.muted),
+                    ),
+                ]);
+            }
+            right.push(Span::styled(age, Style::default().fg(theme.muted)));
+            let others: usize = spans.iter().chain(&right).map(Span::width).sum::<usize>()
+                - spans[title_at].width();
+            // Cut the title rather than let the row wrap
+            spans[title_at] = Span::raw(truncate(&pr.title, width.saturating_sub(others + 2)));
+            let used = others + spans[title_at].width();
+            let gap = width.saturating_sub(used).max(2);
+            spans.push(Span::raw(" ".repeat(gap)));
+            spans.extend(right);
+            ListItem::new(Line::from(spans))
         })
         .collect();
 
-    let list = List::new(items)
+    // Headers go before each author's first PR, once among the pinned PRs
+    // and once among the rest; the sort keeps each run together
+    let grouped = app.grouping_by_author();
+    let is_pinned = |pr: &PullRequest| pinned.contains(&pr.number);
+    let mut rows = Vec::new();
+    let mut list_items = Vec::new();
+    for (i, item) in items.into_iter().enumerate() {
+        let login = &prs[i].author.login;
+        let pin = is_pinned(prs[i]);
+        if grouped && (i == 0 || &prs[i - 1].author.login != login || is_pinned(prs[i - 1]) != pin)
+        {
+            let count = prs
+                .iter()
+                .filter(|pr| &pr.author.login == login && is_pinned(pr) == pin)
+                .count();
+            list_items.push(ListItem::new(Line::styled(
+                format!("{} ({})", login, count),
+                Style::default()
+                    .fg(theme.accent)
+                    .add_modifier(Modifier::BOLD),
+            )));
+            rows.push(None);
+        }
+        list_items.push(item);
+        rows.push(Some(i));
+    }
+
+    let list = List::new(list_items)
         .block(
             Block::default()
                 .title(format!(" {} ", title))
@@ -574,36 +3481,258 @@ fn render_prs(
         .highlight_style(
             Style::default()
                 .add_modifier(Modifier::REVERSED)
-                .fg(Color::Yellow),
+                .fg(theme.highlight),
         );
 
+    let row_of = |index: usize| rows.iter().position(|&r| r == Some(index));
+    // Scroll back far enough to keep the top PR's header in view
+    let offset = row_of(list_state.offset()).map_or(0, |row| {
+        if row > 0 && rows[row - 1].is_none() {
+            row - 1
+        } else {
+            row
+        }
+    });
+    let mut row_state = ListState::default()
+        .with_offset(offset)
+        .with_selected(list_state.selected().and_then(row_of));
+    f.render_stateful_widget(list, area, &mut row_state);
+
+    let top_pr = rows[row_state.offset().min(rows.len())..]
+        .iter()
+        .find_map(|&r| r);
+    *list_state.offset_mut() = top_pr.unwrap_or(0);
+    (rows, row_state.offset())
+}
+
+/// One row per visible instance: agents, PR counts, and any problem
+fn render_overview(f: &mut Frame, app: &App, list_state: &mut ListState, area: Rect) {
+    let theme = &app.theme;
+    let instances: Vec<&Instance> = app
+        .visible_instances()
+        .into_iter()
+        .map(|i| &app.instances[i])
+        .collect();
+    let labels: Vec<String> = instances
+        .iter()
+        .map(|i| app.config.instance_label(i))
+        .collect();
+    let name_width = labels.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+
+    let items: Vec<ListItem> = instances
+        .iter()
+        .zip(&labels)
+        .map(|(instance, label)| {
+            let agents_color = if instance.running_agent_count() > 0 {
+                theme.running
+            } else {
+                theme.stopped
+            };
+            let mut spans = vec![
+                Span::styled(
+                    format!("{:<width$}  ", label, width = name_width),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!("{:>5} agents  ", instance.agent_summary()),
+                    Style::default().fg(agents_color),
+                ),
+                Span::styled(
+                    format!("{:>3} open  ", instance.open_prs.len()),
+                    Style::default().fg(theme.pr_open),
+                ),
+                Span::styled(
+                    format!("{:>3} merged", instance.merged_prs.len()),
+                    Style::default().fg(theme.pr_merged),
+                ),
+            ];
+            let problem = if instance.gh_missing {
+                Some(format!("{} not found", instance.provider.api().program()))
+            } else {
+                instance.last_error.clone()
+            };
+            if let Some(problem) = problem {
+                spans.push(Span::styled(
+                    format!("  ✗ {}", problem),
+                    Style::default().fg(theme.stopped),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(format!(" Overview ({} instances) ", instances.len()))
+                .borders(Borders::ALL),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
     f.render_stateful_widget(list, area, list_state);
 }
 
-fn render_modal(f: &mut Frame, app: &App) {
-    let area = centered_rect(80, 60, f.area());
+fn render_checklist(f: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let Some(pr) = app.selected_pr() else {
+        return;
+    };
+    let repo = app
+        .current_instance()
+        .map(|i| i.state_key())
+        .unwrap_or_default();
+    let checked = app.state.checked_items(&repo, pr.number);
+
+    let items: Vec<ListItem> = app
+        .config
+        .checklist
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let (mark, color) = if checked.contains(item) {
+                ("[x]", theme.running)
+            } else {
+                ("[ ]", theme.muted)
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{} ", mark), Style::default().fg(color)),
+                Span::raw(format!("{}. {}", i + 1, item)),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(format!(
+                    " Review #{} ({}/{}) │ Space: toggle │ Esc: close ",
+                    pr.number,
+                    checked.len(),
+                    app.config.checklist.len()
+                ))
+                .borders(Borders::ALL)
+                .style(Style::default().bg(theme.modal_bg)),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+    let area = centered_rect(60, 40, f.area());
+    let mut state = ListState::default().with_selected(Some(app.checklist_cursor));
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(list, area, &mut state);
+}
 
+fn render_text_input(f: &mut Frame, app: &App, input: &TextInput) {
+    let prompt = Paragraph::new(format!("{}_", input.value)).block(
+        Block::default()
+            .title(format!(" {} (Enter: submit │ Esc: cancel) ", input.prompt))
+            .borders(Borders::ALL)
+            .style(Style::default().bg(app.theme.modal_bg)),
+    );
+    let width = f.area().width * 60 / 100;
+    let area = Rect {
+        x: f.area().x + (f.area().width - width) / 2,
+        y: f.area().y + f.area().height.saturating_sub(3) / 2,
+        width,
+        height: 3.min(f.area().height),
+    };
     f.render_widget(Clear, area);
+    f.render_widget(prompt, area);
+}
 
+fn render_modal(f: &mut Frame, app: &mut App) {
+    let theme = &app.theme;
     let is_loading = *app.modal_loading.lock().unwrap();
     let content = app.modal_content.lock().unwrap().clone();
 
     let title = if is_loading {
-        " Loading... (Press Esc to cancel) "
+        format!(
+            " {} {} (Press Esc to cancel) ",
+            spinner_frame(app.tick),
+            app.modal_title
+        )
+    } else if let Some(action) = &app.pending_action {
+        format!(
+            " {} ({}: confirm │ Esc: cancel) ",
+            app.modal_title,
+            action.confirm_key()
+        )
+    } else if app.summary_pr.is_some() {
+        format!(
+            " {} (Esc: close │ y: copy │ Y: copy as quote │ w: save) ",
+            app.modal_title
+        )
     } else {
-        " PR Summary (Press Esc to close) "
+        format!(" {} (Press Esc to close) ", app.modal_title)
     };
 
-    let modal = Paragraph::new(content).wrap(Wrap { trim: true }).block(
-        Block::default()
-            .title(title)
-            .borders(Borders::ALL)
-            .style(Style::default().bg(Color::DarkGray)),
-    );
+    let mut block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .style(Style::default().bg(theme.modal_bg));
+    if let Some(footer) = &app.modal_footer {
+        block = block.title_bottom(format!(" {} ", footer));
+    }
+    let modal = Paragraph::new(content)
+        .wrap(Wrap { trim: true })
+        .block(block);
+
+    let area = if app.config.modal_auto_size {
+        fitted_rect(&modal, 80, f.area())
+    } else {
+        centered_rect(80, 60, f.area())
+    };
+
+    let lines = modal.line_count(area.width.saturating_sub(2)) as u16;
+    if app.modal_scroll_to_end && !is_loading {
+        app.modal_scroll = u16::MAX;
+        app.modal_scroll_to_end = false;
+    }
+    app.modal_scroll = clamp_scroll(app.modal_scroll, lines, area);
+    let modal = modal.scroll((app.modal_scroll, 0));
 
+    f.render_widget(Clear, area);
     f.render_widget(modal, area);
 }
 
+/// Centered rect `percent_x` wide and just tall enough for the wrapped
+/// paragraph, clamped to the terminal height
+fn fitted_rect(paragraph: &Paragraph, percent_x: u16, r: Rect) -> Rect {
+    let width = r.width * percent_x / 100;
+    // line_count adds the block's top/bottom borders but wraps at the width
+    // it is given, so pass the inner width
+    let lines = paragraph.line_count(width.saturating_sub(2)) as u16;
+    let height = lines.clamp(3, r.height);
+
+    Rect {
+        x: r.x + (r.width - width) / 2,
+        y: r.y + (r.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// Limit `scroll` so the last of a bordered paragraph's `lines` (as counted by
+/// `line_count`, borders included) stops at the bottom of `area`
+fn clamp_scroll(scroll: u16, lines: u16, area: Rect) -> u16 {
+    scroll.min(lines.saturating_sub(area.height))
+}
+
+/// Order PRs pinned first, then by author when `grouped`, then by `mode`
+fn sort_prs(prs: &mut [&PullRequest], mode: SortMode, pinned: &BTreeSet<u32>, grouped: bool) {
+    prs.sort_by(|a, b| {
+        let unpinned = |pr: &PullRequest| !pinned.contains(&pr.number);
+        unpinned(a)
+            .cmp(&unpinned(b))
+            .then_with(|| {
+                if grouped {
+                    a.author.login.cmp(&b.author.login)
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .then_with(|| mode.compare(a, b))
+    });
+}
+
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -623,3 +3752,53 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         ])
         .split(popup_layout[1])[1]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncates_by_display_width() {
+        assert_eq!(truncate("widgets", 7), "widgets");
+        assert_eq!(truncate("widgets", 5), "widg…");
+        // Each of these takes two columns
+        assert_eq!(truncate("日本語のタイトル", 7), "日本語…");
+        assert_eq!(truncate("🚀 launch", 4), "🚀 …");
+        assert_eq!(truncate("widgets", 0), "");
+    }
+
+    fn pr(number: u32, author: &str) -> PullRequest {
+        serde_json::from_value(serde_json::json!({
+            "number": number,
+            "title": format!("PR {}", number),
+            "state": "OPEN",
+            "author": {"login": author},
+            "createdAt": "2024-05-01T12:00:00Z",
+            "headRefName": "branch",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn pinned_prs_stay_first_when_grouped() {
+        let all = [pr(1, "bob"), pr(2, "alice"), pr(3, "bob"), pr(4, "alice")];
+        let mut prs: Vec<&PullRequest> = all.iter().collect();
+        let pinned = BTreeSet::from([1]);
+        let numbers = |prs: &[&PullRequest]| prs.iter().map(|pr| pr.number).collect::<Vec<_>>();
+
+        sort_prs(&mut prs, SortMode::NumberDesc, &pinned, false);
+        assert_eq!(numbers(&prs), [1, 4, 3, 2]);
+        sort_prs(&mut prs, SortMode::NumberDesc, &pinned, true);
+        assert_eq!(numbers(&prs), [1, 4, 2, 3]);
+    }
+
+    #[test]
+    fn steps_within_list_bounds() {
+        assert_eq!(step_by(0, 0, 1), None);
+        assert_eq!(step_by(0, 0, isize::MAX), None);
+        assert_eq!(step_by(2, 5, isize::MIN), Some(0));
+        assert_eq!(step_by(2, 5, isize::MAX), Some(4));
+        assert_eq!(step_by(2, 5, -1), Some(1));
+        assert_eq!(step_by(3, 5, 10), Some(4));
+    }
+}
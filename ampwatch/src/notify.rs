@@ -0,0 +1,11 @@
+/// Show a desktop notification without blocking the UI. Delivery is
+/// best-effort; a missing notification daemon is silently ignored.
+pub fn send(summary: String, body: String) {
+    std::thread::spawn(move || {
+        let _ = notify_rust::Notification::new()
+            .appname("ampwatch")
+            .summary(&summary)
+            .body(&body)
+            .show();
+    });
+}
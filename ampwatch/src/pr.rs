@@ -1,6 +1,13 @@
-use serde::Deserialize;
+use std::cmp::Ordering;
 
-#[derive(Debug, Clone, Deserialize)]
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Fields requested from `gh pr list --json`
+pub const PR_JSON_FIELDS: &str =
+    "number,title,state,author,createdAt,headRefName,headRefOid,mergeable,url,statusCheckRollup,reviewDecision,additions,deletions,changedFiles,isDraft,comments";
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct PullRequest {
     pub number: u32,
     pub title: String,
@@ -10,9 +17,206 @@ pub struct PullRequest {
     pub created_at: String,
     #[serde(rename = "headRefName")]
     pub head_ref_name: String,
+    /// SHA of the head commit
+    #[serde(default, rename = "headRefOid")]
+    pub head_ref_oid: String,
+    /// MERGEABLE, CONFLICTING, or UNKNOWN while GitHub computes it
+    #[serde(default)]
+    pub mergeable: String,
+    #[serde(default)]
+    pub url: String,
+    // Exported as the summarized status, not gh's array of checks
+    #[serde(
+        default,
+        rename(deserialize = "statusCheckRollup"),
+        deserialize_with = "deserialize_checks"
+    )]
+    pub checks: CheckStatus,
+    /// APPROVED, CHANGES_REQUESTED, REVIEW_REQUIRED, or empty if no review
+    /// is required and none was given yet
+    #[serde(
+        default,
+        rename = "reviewDecision",
+        deserialize_with = "null_as_default"
+    )]
+    pub review_decision: String,
+    /// Diff size; zero when gh didn't report it
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub additions: u32,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub deletions: u32,
+    #[serde(default, rename = "changedFiles", deserialize_with = "null_as_default")]
+    pub changed_files: u32,
+    #[serde(default, rename = "isDraft")]
+    pub is_draft: bool,
+    /// Conversation comments, not counting review comments
+    #[serde(default, deserialize_with = "deserialize_count")]
+    pub comments: u32,
+}
+
+/// Combined state of a PR's CI checks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Passing,
+    Failing,
+    Pending,
+    /// No checks configured
+    #[default]
+    None,
+}
+
+/// One entry of `statusCheckRollup`: a check run (`status`/`conclusion`)
+/// or a legacy commit status (`state`)
+#[derive(Deserialize)]
+struct Check {
+    status: Option<String>,
+    conclusion: Option<String>,
+    state: Option<String>,
+}
+
+impl Check {
+    fn is_failing(&self) -> bool {
+        let failed = |s: &str| {
+            matches!(
+                s,
+                "FAILURE"
+                    | "ERROR"
+                    | "TIMED_OUT"
+                    | "CANCELLED"
+                    | "ACTION_REQUIRED"
+                    | "STARTUP_FAILURE"
+            )
+        };
+        self.conclusion.as_deref().is_some_and(failed) || self.state.as_deref().is_some_and(failed)
+    }
+
+    fn is_pending(&self) -> bool {
+        match (&self.status, &self.state) {
+            (Some(status), _) => status != "COMPLETED",
+            (None, Some(state)) => matches!(state.as_str(), "PENDING" | "EXPECTED"),
+            (None, None) => false,
+        }
+    }
+}
+
+/// gh reports `null` for unset fields such as `reviewDecision`
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Default,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// Length of a JSON array whose entries aren't needed, or the number when
+/// re-reading an export
+fn deserialize_count<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Count {
+        List(Vec<serde::de::IgnoredAny>),
+        Number(u32),
+    }
+    Ok(match Option::<Count>::deserialize(deserializer)? {
+        Some(Count::List(items)) => items.len() as u32,
+        Some(Count::Number(n)) => n,
+        None => 0,
+    })
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// Roll the individual checks up into one status: any failure wins, then
+/// anything still running
+fn deserialize_checks<'de, D: Deserializer<'de>>(deserializer: D) -> Result<CheckStatus, D::Error> {
+    let checks = Option::<Vec<Check>>::deserialize(deserializer)?.unwrap_or_default();
+    Ok(if checks.is_empty() {
+        CheckStatus::None
+    } else if checks.iter().any(Check::is_failing) {
+        CheckStatus::Failing
+    } else if checks.iter().any(Check::is_pending) {
+        CheckStatus::Pending
+    } else {
+        CheckStatus::Passing
+    })
+}
+
+impl PullRequest {
+    /// Parsed `created_at`; gh reports ISO-8601 timestamps like `2024-05-01T12:00:00Z`
+    pub fn created(&self) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(&self.created_at)
+            .ok()
+            .map(|t| t.with_timezone(&Utc))
+    }
+
+    /// Identifies the PR's current code: the head SHA, or the branch name
+    /// if gh didn't report one
+    pub fn head_key(&self) -> &str {
+        if self.head_ref_oid.is_empty() {
+            &self.head_ref_name
+        } else {
+            &self.head_ref_oid
+        }
+    }
+
+    pub fn is_conflicting(&self) -> bool {
+        self.mergeable == "CONFLICTING"
+    }
+
+    /// `text` as a Markdown blockquote headed by a link to this PR
+    pub fn markdown_quote(&self, text: &str) -> String {
+        let mut quote = format!("> **[#{} {}]({})**\n>\n", self.number, self.title, self.url);
+        for line in text.trim().lines() {
+            if line.is_empty() {
+                quote.push_str(">\n");
+            } else {
+                quote.push_str(&format!("> {}\n", line));
+            }
+        }
+        quote
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Author {
     pub login: String,
 }
+
+/// PR list ordering, cycled with `s`
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortMode {
+    #[default]
+    NumberDesc,
+    CreatedAsc,
+    CreatedDesc,
+    State,
+}
+
+impl SortMode {
+    pub fn next(self) -> Self {
+        match self {
+            SortMode::NumberDesc => SortMode::CreatedAsc,
+            SortMode::CreatedAsc => SortMode::CreatedDesc,
+            SortMode::CreatedDesc => SortMode::State,
+            SortMode::State => SortMode::NumberDesc,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortMode::NumberDesc => "by number ↓",
+            SortMode::CreatedAsc => "by age ↑",
+            SortMode::CreatedDesc => "by age ↓",
+            SortMode::State => "by state",
+        }
+    }
+
+    pub fn compare(self, a: &PullRequest, b: &PullRequest) -> Ordering {
+        match self {
+            SortMode::NumberDesc => b.number.cmp(&a.number),
+            SortMode::CreatedAsc => a.created().cmp(&b.created()),
+            SortMode::CreatedDesc => b.created().cmp(&a.created()),
+            SortMode::State => a.state.cmp(&b.state).then(b.number.cmp(&a.number)),
+        }
+    }
+}
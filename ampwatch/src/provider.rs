@@ -0,0 +1,264 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pr::{Author, CheckStatus, PullRequest, PR_JSON_FIELDS};
+
+/// Total merged and closed (unmerged) PR counts, for `gh api graphql`
+pub const PR_TOTALS_QUERY: &str = "query=query($owner: String!, $name: String!) { repository(owner: $owner, name: $name) { merged: pullRequests(states: MERGED) { totalCount } closed: pullRequests(states: CLOSED) { totalCount } } }";
+
+/// Runs the provider's CLI in the repo with these arguments, returning
+/// stdout if it succeeded. Supplied by `Instance`, which logs each call.
+pub type Cli<'a> = dyn FnMut(&[&str]) -> Option<Vec<u8>> + 'a;
+
+/// Fetches an instance's PRs from its forge
+pub trait PrProvider: Sync {
+    /// CLI every call runs, e.g. `gh`
+    fn program(&self) -> &'static str;
+
+    /// Shown when `program` isn't installed
+    fn missing_warning(&self) -> &'static str;
+
+    fn open_prs(&self, cli: &mut Cli) -> Option<Vec<PullRequest>>;
+
+    /// Up to `limit` recently merged and as many closed-without-merging
+    /// PRs, newest first
+    fn closed_prs(&self, cli: &mut Cli, limit: u32)
+        -> Option<(Vec<PullRequest>, Vec<PullRequest>)>;
+
+    /// All-time merged and closed counts, if the forge reports them cheaply
+    fn totals(&self, _cli: &mut Cli) -> (Option<u32>, Option<u32>) {
+        (None, None)
+    }
+}
+
+/// Which forge hosts an instance's repo
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    #[default]
+    GitHub,
+    GitLab,
+}
+
+impl Provider {
+    /// Guess from the `origin` remote URL; anything not on a GitLab host is
+    /// treated as GitHub, the default
+    pub fn from_remote(url: &str) -> Self {
+        let host = url
+            .split_once("://")
+            .map_or(url, |(_, rest)| rest)
+            .split(['/', ':'])
+            .next()
+            .unwrap_or_default();
+        if host.to_lowercase().contains("gitlab") {
+            Provider::GitLab
+        } else {
+            Provider::GitHub
+        }
+    }
+
+    pub fn api(self) -> &'static dyn PrProvider {
+        match self {
+            Provider::GitHub => &GitHub,
+            Provider::GitLab => &GitLab,
+        }
+    }
+}
+
+/// GitHub through the `gh` CLI
+pub struct GitHub;
+
+impl PrProvider for GitHub {
+    fn program(&self) -> &'static str {
+        "gh"
+    }
+
+    fn missing_warning(&self) -> &'static str {
+        "gh not found in PATH — install the GitHub CLI to list PRs"
+    }
+
+    fn open_prs(&self, cli: &mut Cli) -> Option<Vec<PullRequest>> {
+        let stdout = cli(&["pr", "list", "--json", PR_JSON_FIELDS])?;
+        serde_json::from_slice(&stdout).ok()
+    }
+
+    fn closed_prs(
+        &self,
+        cli: &mut Cli,
+        limit: u32,
+    ) -> Option<(Vec<PullRequest>, Vec<PullRequest>)> {
+        let limit = limit.to_string();
+        let mut list = |state: &str, extra: &[&str]| -> Option<Vec<PullRequest>> {
+            let mut args = vec!["pr", "list", "--state", state];
+            args.extend_from_slice(extra);
+            args.extend(["--limit", &limit, "--json", PR_JSON_FIELDS]);
+            serde_json::from_slice(&cli(&args)?).ok()
+        };
+        let merged = list("merged", &[])?;
+        // gh's closed state includes merged PRs
+        let closed = list("closed", &["--search", "is:unmerged"])?;
+        Some((merged, closed))
+    }
+
+    fn totals(&self, cli: &mut Cli) -> (Option<u32>, Option<u32>) {
+        // gh pr list can't count, so ask GraphQL how many there are in all
+        let totals = cli(&[
+            "api",
+            "graphql",
+            "-F",
+            "owner={owner}",
+            "-F",
+            "name={repo}",
+            "-f",
+            PR_TOTALS_QUERY,
+        ])
+        .and_then(|stdout| serde_json::from_slice::<serde_json::Value>(&stdout).ok());
+        let count = |key: &str| {
+            totals.as_ref()?["data"]["repository"][key]["totalCount"]
+                .as_u64()
+                .map(|n| n as u32)
+        };
+        (count("merged"), count("closed"))
+    }
+}
+
+/// GitLab through the `glab` CLI. Merge requests are mapped onto
+/// `PullRequest`; CI and review state aren't in `glab mr list` output.
+pub struct GitLab;
+
+/// The fields of a GitLab merge request that `PullRequest` has room for
+#[derive(Deserialize)]
+struct MergeRequest {
+    iid: u32,
+    title: String,
+    /// `opened`, `merged`, `closed`, or `locked`
+    state: String,
+    author: GitLabUser,
+    #[serde(default)]
+    created_at: String,
+    #[serde(default)]
+    source_branch: String,
+    #[serde(default)]
+    sha: Option<String>,
+    #[serde(default)]
+    web_url: String,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    has_conflicts: bool,
+    #[serde(default)]
+    user_notes_count: u32,
+}
+
+#[derive(Deserialize)]
+struct GitLabUser {
+    username: String,
+}
+
+impl From<MergeRequest> for PullRequest {
+    fn from(mr: MergeRequest) -> Self {
+        let state = match mr.state.as_str() {
+            "opened" | "locked" => "OPEN",
+            "merged" => "MERGED",
+            "closed" => "CLOSED",
+            other => other,
+        };
+        PullRequest {
+            number: mr.iid,
+            title: mr.title,
+            state: state.to_string(),
+            author: Author {
+                login: mr.author.username,
+            },
+            created_at: mr.created_at,
+            head_ref_name: mr.source_branch,
+            head_ref_oid: mr.sha.unwrap_or_default(),
+            mergeable: if mr.has_conflicts {
+                "CONFLICTING"
+            } else {
+                "MERGEABLE"
+            }
+            .to_string(),
+            url: mr.web_url,
+            checks: CheckStatus::None,
+            review_decision: String::new(),
+            additions: 0,
+            deletions: 0,
+            changed_files: 0,
+            is_draft: mr.draft,
+            comments: mr.user_notes_count,
+        }
+    }
+}
+
+impl GitLab {
+    fn list(&self, cli: &mut Cli, args: &[&str]) -> Option<Vec<PullRequest>> {
+        let args: Vec<&str> = ["mr", "list", "--output", "json"]
+            .into_iter()
+            .chain(args.iter().copied())
+            .collect();
+        let stdout = cli(&args)?;
+        let mrs: Vec<MergeRequest> = serde_json::from_slice(&stdout).ok()?;
+        Some(mrs.into_iter().map(PullRequest::from).collect())
+    }
+}
+
+impl PrProvider for GitLab {
+    fn program(&self) -> &'static str {
+        "glab"
+    }
+
+    fn missing_warning(&self) -> &'static str {
+        "glab not found in PATH — install the GitLab CLI to list merge requests"
+    }
+
+    fn open_prs(&self, cli: &mut Cli) -> Option<Vec<PullRequest>> {
+        self.list(cli, &[])
+    }
+
+    fn closed_prs(
+        &self,
+        cli: &mut Cli,
+        limit: u32,
+    ) -> Option<(Vec<PullRequest>, Vec<PullRequest>)> {
+        // Unlike gh, glab lists merged and closed MRs separately
+        let limit = limit.to_string();
+        let merged = self.list(cli, &["--merged", "--per-page", &limit])?;
+        let closed = self.list(cli, &["--closed", "--per-page", &limit])?;
+        Some((merged, closed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_provider_from_remote() {
+        for (url, provider) in [
+            ("git@github.com:o/r.git", Provider::GitHub),
+            ("https://github.com/o/r", Provider::GitHub),
+            ("git@gitlab.com:o/r.git", Provider::GitLab),
+            ("https://gitlab.example.com/group/o/r.git", Provider::GitLab),
+            ("ssh://git@example.com/gitlab/r.git", Provider::GitHub),
+        ] {
+            assert!(Provider::from_remote(url) == provider, "{}", url);
+        }
+    }
+
+    #[test]
+    fn maps_merge_requests_onto_prs() {
+        let json = br#"[{"iid": 7, "title": "Add parser", "state": "opened",
+            "author": {"username": "impl-bot"}, "created_at": "2024-05-01T12:00:00Z",
+            "source_branch": "impl-alpha/parser", "sha": "abc", "draft": true,
+            "has_conflicts": true, "web_url": "https://gitlab.com/o/r/-/merge_requests/7",
+            "user_notes_count": 3}]"#;
+        let prs = GitLab.open_prs(&mut |_| Some(json.to_vec())).unwrap();
+
+        let pr = &prs[0];
+        assert_eq!((pr.number, pr.state.as_str()), (7, "OPEN"));
+        assert_eq!(pr.author.login, "impl-bot");
+        assert_eq!(pr.head_ref_name, "impl-alpha/parser");
+        assert!(pr.is_conflicting() && pr.is_draft);
+        assert_eq!(pr.comments, 3);
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::thread;
+
+use crate::agent::LogScan;
+use crate::config::Config;
+use crate::github::GitHubApi;
+use crate::instance::{discover_instances, merge_duplicates, orphaned_sessions, Instance};
+use crate::runner::CommandRunner;
+
+/// Upper bound on instances refreshed at once; each runs tmux and two gh calls
+const MAX_REFRESH_THREADS: usize = 4;
+
+/// Settings every refresh shares, built once from the config
+#[derive(Clone)]
+pub struct RefreshOptions {
+    /// Fetches GitHub PRs without gh when `github_api` is on
+    pub github: Option<GitHubApi>,
+    /// Why the GitHub API client couldn't be set up, warned on each refresh
+    pub github_error: Option<String>,
+    /// Extra globs for log directories
+    pub log_patterns: Vec<String>,
+    pub log_scan: LogScan,
+    /// Recently merged PRs fetched for instances not extended with `M`
+    pub default_merged_limit: u32,
+}
+
+impl RefreshOptions {
+    pub fn new(config: &Config) -> Self {
+        let (github, github_error) = match config.github_api.then(GitHubApi::new) {
+            Some(Ok(api)) => (Some(api), None),
+            Some(Err(e)) => (
+                None,
+                Some(format!(
+                    "github_api is on but its HTTP client failed: {}; using gh",
+                    e
+                )),
+            ),
+            None => (None, None),
+        };
+        Self {
+            github,
+            github_error,
+            log_patterns: config.log_patterns.clone(),
+            log_scan: LogScan::new(config),
+            default_merged_limit: config.merged_pr_limit.max(1),
+        }
+    }
+}
+
+/// Freshly discovered and refreshed instances
+pub struct Refresh {
+    pub instances: Vec<Instance>,
+    pub warnings: Vec<String>,
+    pub env_warnings: Vec<String>,
+}
+
+/// Discover instances and refresh each one. Slow (shells out to tmux and
+/// gh), so the TUI runs it on a background thread; `ampwatch status` calls
+/// it directly. `previous` is the last snapshot, used to resume log scans
+/// where they left off. `merged_pr_limits` holds the instances whose merged
+/// PR list was extended with `M`; the rest fetch the default in `options`.
+/// `on_refreshed` is called from the worker threads as each instance
+/// finishes.
+pub fn fetch_instances(
+    runner: &dyn CommandRunner,
+    options: &RefreshOptions,
+    previous: Vec<Instance>,
+    merged_pr_limits: HashMap<String, u32>,
+    on_refreshed: &(dyn Fn(&Instance) + Sync),
+) -> Refresh {
+    // Discover all running instances
+    let discovery = discover_instances(runner, &options.log_patterns);
+    let mut warnings = discovery.warnings;
+    let mut env_warnings = Vec::new();
+    if discovery.tmux_missing {
+        env_warnings.push("tmux not found in PATH — ampwatch requires tmux".to_string());
+    }
+    env_warnings.extend(options.github_error.clone());
+    let github = options.github.as_ref().filter(|api| {
+        let has_token = api.ensure_token(runner);
+        if !has_token {
+            env_warnings.push(
+                "github_api is on but no token was found — set GITHUB_TOKEN or run `gh auth login`; using gh".to_string(),
+            );
+        }
+        has_token
+    });
+
+    // Convert to vec and sort by repo name for stable ordering
+    let mut instances: Vec<Instance> = discovery.instances.into_values().collect();
+    instances.sort_by_key(|a| a.repo_name());
+
+    for instance in &mut instances {
+        instance.carry_over(&previous);
+        instance.merged_pr_limit = merged_pr_limits
+            .get(&instance.id)
+            .copied()
+            .unwrap_or(options.default_merged_limit);
+    }
+
+    // Refresh instances concurrently, splitting them across at most
+    // MAX_REFRESH_THREADS threads to bound the number of gh processes
+    let per_thread = instances.len().div_ceil(MAX_REFRESH_THREADS).max(1);
+    thread::scope(|scope| {
+        for chunk in instances.chunks_mut(per_thread) {
+            scope.spawn(move || {
+                for instance in chunk {
+                    instance.refresh(runner, github, &options.log_scan);
+                    on_refreshed(instance);
+                }
+            });
+        }
+    });
+
+    for instance in &instances {
+        for warning in &instance.log_warnings {
+            warnings.push(format!("{}: {}", instance.repo_name(), warning));
+        }
+    }
+    if github.is_some_and(GitHubApi::rate_limited) {
+        env_warnings.push("GitHub API rate limit reached — using gh until it resets".to_string());
+    }
+    for instance in instances.iter().filter(|i| i.gh_missing) {
+        let warning = instance.provider.api().missing_warning().to_string();
+        if !env_warnings.contains(&warning) {
+            env_warnings.push(warning);
+        }
+    }
+
+    let orphans = orphaned_sessions(&discovery.sessions, &instances);
+    if !orphans.is_empty() {
+        warnings.push(format!(
+            "{} orphaned amptown tmux session(s); run `ampwatch --doctor` to clean up",
+            orphans.len()
+        ));
+    }
+
+    // Repo paths are only known after refreshing. Merging comes after the
+    // orphan check, which looks sessions up by instance id.
+    merge_duplicates(&mut instances);
+
+    Refresh {
+        instances,
+        warnings,
+        env_warnings,
+    }
+}
@@ -0,0 +1,92 @@
+use std::io;
+use std::path::Path;
+use std::process::{Command, Output};
+
+use crate::config::Config;
+
+/// Runs external programs (`tmux`, `gh`, `glab`) to completion. Discovery and
+/// refresh go through this so tests can substitute canned output.
+pub trait CommandRunner: Sync {
+    fn run(&self, program: &str, args: &[&str], cwd: Option<&Path>) -> io::Result<Output>;
+}
+
+/// Runs commands for real, substituting the configured `gh_bin`,
+/// `glab_bin`, and `tmux_bin` for `gh`, `glab`, and `tmux`
+#[derive(Clone)]
+pub struct SystemRunner {
+    gh: String,
+    glab: String,
+    tmux: String,
+}
+
+impl SystemRunner {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            gh: config.gh_bin.clone(),
+            glab: config.glab_bin.clone(),
+            tmux: config.tmux_bin.clone(),
+        }
+    }
+}
+
+impl CommandRunner for SystemRunner {
+    fn run(&self, program: &str, args: &[&str], cwd: Option<&Path>) -> io::Result<Output> {
+        let program = match program {
+            "gh" => &self.gh,
+            "glab" => &self.glab,
+            "tmux" => &self.tmux,
+            other => other,
+        };
+        let mut cmd = Command::new(program);
+        cmd.args(args);
+        if let Some(cwd) = cwd {
+            cmd.current_dir(cwd);
+        }
+        cmd.output()
+    }
+}
+
+#[cfg(test)]
+pub mod mock {
+    use std::collections::HashMap;
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::ExitStatus;
+
+    use super::*;
+
+    /// Replies to commands with canned output, keyed by the full command
+    /// line (`gh pr list --json ...`). Unknown commands fail like a missing
+    /// tmux session would.
+    #[derive(Default)]
+    pub struct MockRunner {
+        replies: HashMap<String, (i32, String)>,
+    }
+
+    impl MockRunner {
+        pub fn reply(mut self, command: &str, stdout: &str) -> Self {
+            self.replies
+                .insert(command.to_string(), (0, stdout.to_string()));
+            self
+        }
+    }
+
+    impl CommandRunner for MockRunner {
+        fn run(&self, program: &str, args: &[&str], _cwd: Option<&Path>) -> io::Result<Output> {
+            let command = std::iter::once(program)
+                .chain(args.iter().copied())
+                .collect::<Vec<_>>()
+                .join(" ");
+            let (code, stdout) = self
+                .replies
+                .get(&command)
+                .cloned()
+                .unwrap_or((1, String::new()));
+            Ok(Output {
+                // Wait statuses keep the exit code in the second byte
+                status: ExitStatus::from_raw(code << 8),
+                stdout: stdout.into_bytes(),
+                stderr: Vec::new(),
+            })
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::ThemePreset;
+use crate::pr::SortMode;
+
+/// Directory holding ampwatch's config and state files (`~/.config/ampwatch`)
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".config").join("ampwatch"))
+}
+
+/// Schema version written to the state file. Bump when a field changes
+/// meaning; adding fields with `#[serde(default)]` doesn't need a bump.
+const STATE_VERSION: u32 = 1;
+
+/// UI state persisted between sessions, loaded once at startup and saved
+/// on every change and on exit
+#[derive(Serialize, Deserialize)]
+pub struct State {
+    #[serde(default)]
+    pub version: u32,
+    /// Pinned PR numbers, keyed by repo path
+    #[serde(default)]
+    pub pinned_prs: BTreeMap<String, BTreeSet<u32>>,
+    /// Ticked review checklist items, keyed by repo path then PR number
+    #[serde(default)]
+    pub checklists: BTreeMap<String, BTreeMap<u32, BTreeSet<String>>>,
+    /// Where the UI was left at exit
+    #[serde(default)]
+    pub ui: UiState,
+    /// Keys written by newer ampwatch versions, preserved on save
+    #[serde(flatten)]
+    extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            version: STATE_VERSION,
+            pinned_prs: BTreeMap::new(),
+            checklists: BTreeMap::new(),
+            ui: UiState::default(),
+            extra: serde_json::Map::new(),
+        }
+    }
+}
+
+/// Selection and filters saved at exit, so the next session reopens where
+/// this one left off. Indices may be stale and are checked when restored.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiState {
+    /// `Instance::state_key` of the selected instance
+    pub instance: Option<String>,
+    pub tab: usize,
+    /// Selected row of the tab's PR list
+    pub pr: usize,
+    pub only_open_prs: bool,
+    pub hide_stopped_agents: bool,
+    pub hide_drafts: bool,
+    pub sort_mode: SortMode,
+    /// Palette switched to with `t`; `None` follows the config
+    pub theme: Option<ThemePreset>,
+    /// Only restored if the instance still has PRs by this login
+    pub author_filter: Option<String>,
+    pub search_query: String,
+}
+
+impl State {
+    fn path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join("state.json"))
+    }
+
+    /// Load the state file, falling back to an empty state if missing. An
+    /// unparseable file is moved aside to `state.json.corrupt` rather than
+    /// silently overwritten on the next save.
+    pub fn load() -> Self {
+        let Some(path) = Self::path() else {
+            return Self::default();
+        };
+        let Ok(content) = fs::read_to_string(&path) else {
+            return Self::default();
+        };
+        match serde_json::from_str::<Self>(&content) {
+            Ok(mut state) => {
+                state.version = state.version.max(STATE_VERSION);
+                state
+            }
+            Err(_) => {
+                let _ = fs::rename(&path, path.with_extension("json.corrupt"));
+                Self::default()
+            }
+        }
+    }
+
+    /// Best-effort atomic save: write a temp file, then rename it over the
+    /// state file so a crash or a concurrent ampwatch never leaves it
+    /// half-written. Failing to persist should never interrupt the UI.
+    pub fn save(&self) {
+        let Some(path) = Self::path() else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let Ok(json) = serde_json::to_string_pretty(self) else {
+            return;
+        };
+        let tmp = path.with_extension(format!("json.tmp.{}", std::process::id()));
+        if fs::write(&tmp, json).is_ok() && fs::rename(&tmp, &path).is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+    }
+
+    pub fn pinned(&self, repo: &str) -> BTreeSet<u32> {
+        self.pinned_prs.get(repo).cloned().unwrap_or_default()
+    }
+
+    pub fn checked_items(&self, repo: &str, number: u32) -> BTreeSet<String> {
+        self.checklists
+            .get(repo)
+            .and_then(|prs| prs.get(&number))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn toggle_checklist_item(&mut self, repo: &str, number: u32, item: &str) {
+        let prs = self.checklists.entry(repo.to_string()).or_default();
+        let items = prs.entry(number).or_default();
+        if !items.remove(item) {
+            items.insert(item.to_string());
+        }
+        if items.is_empty() {
+            prs.remove(&number);
+        }
+        if prs.is_empty() {
+            self.checklists.remove(repo);
+        }
+    }
+
+    /// Toggle a PR's pinned state, returning whether it is now pinned
+    pub fn toggle_pin(&mut self, repo: &str, number: u32) -> bool {
+        let pins = self.pinned_prs.entry(repo.to_string()).or_default();
+        let pinned = if pins.remove(&number) {
+            false
+        } else {
+            pins.insert(number);
+            true
+        };
+        if pins.is_empty() {
+            self.pinned_prs.remove(repo);
+        }
+        pinned
+    }
+}
@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+use std::io::{self, IsTerminal};
+
+use crossterm::style::Stylize;
+
+use crate::config::Config;
+use crate::refresh::{fetch_instances, RefreshOptions};
+use crate::runner::SystemRunner;
+
+/// Print a table of instances, running agents, and open PRs for
+/// `ampwatch status`. Colored only when stdout is a terminal, so the output
+/// can be piped into status lines and scripts.
+pub fn run(config: &Config) {
+    let runner = SystemRunner::new(config);
+    let refresh = fetch_instances(
+        &runner,
+        &RefreshOptions::new(config),
+        Vec::new(),
+        HashMap::new(),
+        &|_| {},
+    );
+    for warning in refresh.env_warnings.iter().chain(&refresh.warnings) {
+        eprintln!("ampwatch: {}", warning);
+    }
+    if refresh.instances.is_empty() {
+        println!("No amptown instances found");
+        return;
+    }
+
+    let color = io::stdout().is_terminal();
+    let rows: Vec<[String; 4]> = refresh
+        .instances
+        .iter()
+        .map(|instance| {
+            [
+                config.instance_label(instance),
+                instance.id.clone(),
+                instance.agent_summary(),
+                instance.open_prs.len().to_string(),
+            ]
+        })
+        .collect();
+    let header = ["INSTANCE", "ID", "RUNNING", "OPEN PRS"];
+    let widths: Vec<usize> = (0..header.len())
+        .map(|col| {
+            rows.iter()
+                .map(|row| row[col].chars().count())
+                .chain([header[col].len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    // Pad before styling; escape codes would throw the widths off
+    let pad = |cells: &[String]| -> Vec<String> {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, &width)| format!("{:<width$}", cell))
+            .collect()
+    };
+    let print = |cells: Vec<String>| println!("{}", cells.join("  ").trim_end());
+
+    let mut header = pad(&header.map(String::from));
+    if color {
+        header = header.into_iter().map(|h| h.bold().to_string()).collect();
+    }
+    print(header);
+
+    for (row, instance) in rows.iter().zip(&refresh.instances) {
+        let mut cells = pad(row);
+        if color {
+            cells[0] = cells[0].as_str().bold().to_string();
+            cells[2] = if instance.running_agent_count() > 0 {
+                cells[2].as_str().green().to_string()
+            } else {
+                cells[2].as_str().red().to_string()
+            };
+        }
+        print(cells);
+    }
+}
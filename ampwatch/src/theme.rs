@@ -0,0 +1,161 @@
+use std::str::FromStr;
+
+use ratatui::style::Color;
+
+use crate::config::{ThemeConfig, ThemePreset};
+
+/// How many colors the terminal can display
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ColorDepth {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+}
+
+impl ColorDepth {
+    /// Detect color support from `COLORTERM` and `TERM`. Only consoles known
+    /// to lack 256 colors (`linux`, `vt*`, `dumb`) get the 16-color palette,
+    /// unless `basic_colors` asks for it.
+    pub fn detect(basic_colors: bool) -> Self {
+        if basic_colors {
+            return ColorDepth::Ansi16;
+        }
+        let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorDepth::TrueColor;
+        }
+        let term = std::env::var("TERM").unwrap_or_default();
+        if term == "linux" || term == "dumb" || term.starts_with("vt") {
+            ColorDepth::Ansi16
+        } else {
+            ColorDepth::Ansi256
+        }
+    }
+}
+
+/// Colors used by the render functions
+#[derive(Clone)]
+pub struct Theme {
+    /// Plain text where the terminal default isn't used
+    pub text: Color,
+    pub accent: Color,
+    pub live: Color,
+    pub muted: Color,
+    pub highlight: Color,
+    pub running: Color,
+    pub stopped: Color,
+    /// Running, but no log output for a while
+    pub stuck: Color,
+    pub reviewer: Color,
+    pub implementer: Color,
+    pub pr_number: Color,
+    pub pr_open: Color,
+    pub pr_merged: Color,
+    pub pr_closed: Color,
+    pub pr_unknown: Color,
+    pub modal_bg: Color,
+}
+
+impl Theme {
+    /// The `preset` palette for this terminal, with the `[theme]` overrides
+    pub fn load(preset: ThemePreset, config: &ThemeConfig) -> Self {
+        let depth = ColorDepth::detect(config.basic_colors);
+        Self::for_depth(preset, depth).with_overrides(config, depth)
+    }
+
+    pub fn for_depth(preset: ThemePreset, depth: ColorDepth) -> Self {
+        match (preset, depth) {
+            (ThemePreset::Dark, ColorDepth::TrueColor | ColorDepth::Ansi256) => Self::default(),
+            (ThemePreset::Dark, ColorDepth::Ansi16) => Self::basic(),
+            (ThemePreset::Light, ColorDepth::TrueColor | ColorDepth::Ansi256) => Self::light(),
+            (ThemePreset::Light, ColorDepth::Ansi16) => Self::light_basic(),
+        }
+    }
+
+    /// Apply user overrides; colors are validated when the config loads.
+    /// On a 16-color terminal, hex and 256-color overrides are skipped so the
+    /// basic palette stays legible.
+    pub fn with_overrides(mut self, overrides: &ThemeConfig, depth: ColorDepth) -> Self {
+        for (slot, value) in [
+            (&mut self.accent, &overrides.accent),
+            (&mut self.highlight, &overrides.highlight),
+            (&mut self.running, &overrides.running),
+            (&mut self.stopped, &overrides.stopped),
+            (&mut self.pr_open, &overrides.pr_open),
+            (&mut self.pr_merged, &overrides.pr_merged),
+            (&mut self.pr_closed, &overrides.pr_closed),
+            (&mut self.pr_unknown, &overrides.pr_unknown),
+        ] {
+            let Some(color) = value.as_deref().and_then(|c| Color::from_str(c).ok()) else {
+                continue;
+            };
+            let basic = !matches!(color, Color::Rgb(..) | Color::Indexed(16..));
+            if basic || depth != ColorDepth::Ansi16 {
+                *slot = color;
+            }
+        }
+        self
+    }
+
+    /// Palette restricted to colors that stay legible on 16-color consoles,
+    /// where DarkGray often renders as black
+    pub fn basic() -> Self {
+        Self {
+            muted: Color::Gray,
+            stuck: Color::Yellow,
+            modal_bg: Color::Blue,
+            ..Self::default()
+        }
+    }
+
+    /// Palette for light terminal backgrounds, where yellow and white text
+    /// wash out
+    pub fn light() -> Self {
+        Self {
+            text: Color::Black,
+            accent: Color::Blue,
+            muted: Color::Indexed(243),
+            highlight: Color::Indexed(130), // Dark orange
+            stuck: Color::Indexed(166),
+            pr_number: Color::Indexed(130),
+            pr_unknown: Color::Black,
+            modal_bg: Color::Indexed(254),
+            ..Self::default()
+        }
+    }
+
+    /// Light palette restricted to the 16 basic colors
+    pub fn light_basic() -> Self {
+        Self {
+            highlight: Color::Magenta,
+            stuck: Color::Red,
+            pr_number: Color::Blue,
+            muted: Color::DarkGray,
+            modal_bg: Color::White,
+            ..Self::light()
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            text: Color::White,
+            accent: Color::Cyan,
+            live: Color::Green,
+            muted: Color::DarkGray,
+            highlight: Color::Yellow,
+            running: Color::Green,
+            stopped: Color::Red,
+            stuck: Color::Indexed(214), // Amber
+            reviewer: Color::Blue,
+            implementer: Color::Magenta,
+            pr_number: Color::Yellow,
+            pr_open: Color::Green,
+            pr_merged: Color::Magenta,
+            pr_closed: Color::Red,
+            pr_unknown: Color::White,
+            modal_bg: Color::DarkGray,
+        }
+    }
+}
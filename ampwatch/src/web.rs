@@ -0,0 +1,201 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{IpAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::Result;
+
+use crate::agent::AgentStatus;
+use crate::instance::Instance;
+use crate::pr::PullRequest;
+
+/// Instances as of the last refresh, shared with the web server threads;
+/// `None` until the first refresh completes
+pub type Snapshot = Arc<Mutex<Option<Vec<Instance>>>>;
+
+/// How long a client gets to send its request or take the response
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Clients served at once; more wait in the listen backlog
+const WORKERS: usize = 4;
+
+/// Serve a read-only, auto-refreshing HTML dashboard on `addr:port`
+pub fn serve(addr: IpAddr, port: u16, snapshot: Snapshot) -> Result<()> {
+    let listener = TcpListener::bind((addr, port))?;
+    for _ in 0..WORKERS {
+        // Each worker accepts on its own handle, so a slow client holds up
+        // only one of them
+        let listener = listener.try_clone()?;
+        let snapshot = Arc::clone(&snapshot);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let _ = handle(stream, &snapshot);
+            }
+        });
+    }
+    Ok(())
+}
+
+fn handle(mut stream: TcpStream, snapshot: &Snapshot) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+
+    let (status, body) = if request_line.starts_with("GET ") {
+        let instances = snapshot.lock().unwrap().clone();
+        match instances {
+            Some(instances) => ("200 OK", render(&instances)),
+            None => ("200 OK", render_loading()),
+        }
+    } else {
+        ("405 Method Not Allowed", "read-only".to_string())
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Page start, reloading every `refresh_secs`
+fn head(refresh_secs: u32) -> String {
+    format!(
+        "<!doctype html><html><head><meta charset=\"utf-8\">\
+         <meta http-equiv=\"refresh\" content=\"{}\">\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\
+         <title>ampwatch</title>\
+         <style>body{{font-family:monospace;background:#111;color:#ddd;margin:1em}}\
+         h2{{color:#5fd7ff}}.running{{color:#5f5}}.stopped{{color:#f55}}.muted{{color:#888}}\
+         td{{padding:0 1em 0 0}}</style></head><body><h1>ampwatch</h1>",
+        refresh_secs
+    )
+}
+
+/// Shown before the first refresh, so an empty list isn't mistaken for no
+/// instances
+fn render_loading() -> String {
+    let mut html = head(1);
+    html.push_str("<p class=\"muted\">Loading…</p></body></html>");
+    html
+}
+
+fn render(instances: &[Instance]) -> String {
+    let mut html = head(5);
+
+    if instances.is_empty() {
+        html.push_str("<p class=\"muted\">No amptown instances found.</p>");
+    }
+
+    for instance in instances {
+        html.push_str(&format!(
+            "<h2>{} <span class=\"muted\">({}) {} running</span></h2>",
+            escape(&instance.repo_name()),
+            escape(&instance.id),
+            instance.agent_summary()
+        ));
+
+        html.push_str("<table>");
+        for agent in &instance.agents {
+            let (class, label) = match agent.status() {
+                AgentStatus::Running => ("running", "running"),
+                AgentStatus::Completed => ("muted", "completed"),
+                AgentStatus::Crashed => ("stopped", "crashed"),
+                AgentStatus::Idle => ("muted", "idle"),
+            };
+            html.push_str(&format!(
+                "<tr><td>{}</td><td class=\"{}\">{}</td><td>iter {}</td></tr>",
+                escape(&agent.name),
+                class,
+                label,
+                agent.iterations
+            ));
+        }
+        html.push_str("</table>");
+
+        render_prs(&mut html, "Open PRs", &instance.open_prs);
+        render_prs(&mut html, "Merged PRs", &instance.merged_prs);
+        render_prs(&mut html, "Closed PRs", &instance.closed_prs);
+    }
+
+    html.push_str("</body></html>");
+    html
+}
+
+fn render_prs(html: &mut String, title: &str, prs: &[PullRequest]) {
+    html.push_str(&format!("<h3>{} ({})</h3><ul>", title, prs.len()));
+    for pr in prs {
+        html.push_str(&format!(
+            "<li>#{} {} <span class=\"muted\">by {}</span></li>",
+            pr.number,
+            escape(&pr.title),
+            escape(&pr.author.login)
+        ));
+    }
+    html.push_str("</ul>");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn renders_empty_state() {
+        assert!(render(&[]).contains("No amptown instances found."));
+    }
+
+    #[test]
+    fn serves_loading_page_until_first_refresh() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+        let snapshot = Snapshot::default();
+        serve("127.0.0.1".parse().unwrap(), port, Arc::clone(&snapshot)).unwrap();
+        let get = || {
+            let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+            stream.write_all(b"GET / HTTP/1.1\r\n\r\n").unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+
+        let loading = get();
+        assert!(loading.contains("Loading…"));
+        assert!(!loading.contains("No amptown instances found."));
+
+        *snapshot.lock().unwrap() = Some(Vec::new());
+        assert!(get().contains("No amptown instances found."));
+    }
+
+    #[test]
+    fn escapes_user_text() {
+        let mut instance = Instance::new("<id>".to_string());
+        instance.open_prs = vec![serde_json::from_value(serde_json::json!({
+            "number": 7,
+            "title": "Fix <script>alert(\"x\")</script> & more",
+            "state": "OPEN",
+            "author": {"login": "impl-bot"},
+            "createdAt": "2024-05-01T12:00:00Z",
+            "headRefName": "branch",
+        }))
+        .unwrap()];
+        let html = render(&[instance]);
+
+        assert!(html.contains("(&lt;id&gt;)"));
+        assert!(html.contains("Fix &lt;script&gt;alert(&quot;x&quot;)&lt;/script&gt; &amp; more"));
+        assert!(!html.contains("<script>"));
+        assert!(!html.contains("No amptown instances found."));
+    }
+}